@@ -1,8 +1,9 @@
 app-title = Tesseract
 about = About
 view = View
-hold-space-to-start = Hold Space to start
-tap-space-to-stop = Tap Space to stop
+hold-to-start = Hold { $trigger } to start
+tap-to-stop = Tap { $trigger } to stop
+tap-to-inspect = Tap { $trigger } to start inspecting
 page-id = Page { $num }
 git-description = Git commit {$hash} on {$date}
 your-solving-record = Your solving record
@@ -12,3 +13,218 @@ cancel = Cancel
 remove = Remove
 remove-solve = Remove this solve
 remove-all-solves-for-puzzle = Remove all solves for this puzzle
+fmc-start = Start attempt
+fmc-hint = You have one hour to find a solution of at most { $limit } moves
+fmc-five-minutes = 5 minutes remaining
+fmc-one-minute = 1 minute remaining
+fmc-solution-placeholder = Type your solution
+fmc-submit = Submit solution
+fmc-give-up = Give up
+fmc-empty = Type a solution before submitting
+fmc-invalid-move = "{ $token }" is not an allowed move
+fmc-too-long = Your solution is { $count } moves long, the limit is { $limit }
+fmc-not-solved = This solution does not solve the scramble
+manual-entry = Type times manually
+manual-entry-placeholder = 12.34
+manual-entry-add = Add solve
+manual-entry-hint = Type a time such as 12.34 or 1:02.55, DNF, or add + for a +2 penalty
+manual-entry-invalid = That doesn't look like a time
+hide-timer = Hide time while solving
+focus-mode = Focus mode
+touch-pads = Touch pads
+touch-pad-left = Left hand
+touch-pad-right = Right hand
+touch-pads-start = Hold both pads until they turn green, then lift your hands to start
+touch-pads-inspect = Tap both pads to start inspecting
+touch-pads-stop = Put both hands back on the pads to stop
+solving = Solving
+training = Training
+training-off = Off
+training-subtitle = { $subset } training
+training-description = Practise a chosen group of 3x3x3 cases. Each scramble sets up a random case from the ones selected below, and the case is recorded with every solve. ZBLL is practised a whole set at a time, each set named after its corner orientation.
+training-case-group = Case group
+training-cases = Cases
+training-no-cases = No cases are selected. Choose some on the training page to get training scrambles.
+select-all = Select all
+select-none = Select none
+case-solves = { $count ->
+    [one] 1 solve
+   *[other] { $count } solves
+}
+trainer = Algorithm trainer
+trainer-recognised = I know this case
+trainer-hint = Press { $trigger } as soon as you recognise the case. Cases you are slow on or miss come up more often.
+trainer-missed = I didn't know it
+trainer-known = I knew it
+settings = Settings
+timer = Timer
+precision = Precision (seconds)
+wca-rounding = Round recorded times to hundredths, as WCA rules do
+inspection = Inspection
+inspection-description = 15 seconds to inspect the cube, started by tapping { $trigger }. Starting the solve after 15 seconds adds a +2 penalty, and after 17 a DNF.
+edit = Edit
+undo = Undo
+redo = Redo
+solve-removed = Solve removed
+solves-removed = All solves removed
+penalty-changed = Penalty changed
+penalty-none = OK
+comment-placeholder = Comment on this solve
+comment-save = Save comment
+backups = Backups
+backups-description = A snapshot of all your records is taken every hour while they change, and before solves are cleared, records are upgraded or a backup is restored.
+backup-now = Back up now
+snapshots = Snapshots
+backup-scheduled = Scheduled
+backup-manual = Taken by hand
+backup-before-remove = Before removing a solve
+backup-before-clear = Before removing all solves
+backup-before-restore = Before restoring a backup
+restore = Restore
+restore-backup = Restore this backup
+restore-backup-description = Your records will be replaced by the ones in this snapshot, and records started since will be emptied. A snapshot of your current records is taken first.
+write-failed = Your records could not be saved: { $error }
+backup-before-upgrade = Before upgrading records
+record-newer = These records were saved by a newer version of Tesseract. They are shown, but changes won't be saved.
+record-unreadable = These records could not be read, so changes won't be saved over them: { $error }
+phases = Phases
+phases-description = Press { $trigger } to end each phase, such as cross, F2L, OLL and PLL, and record split times
+solve = Solve
+solve-missing = This solve is no longer in your records
+solve-time = Time
+solve-date = Date
+solve-case = Case
+solve-solution = Solution
+splits = Splits
+phase = Phase { $number }
+averages = Averages
+average-ending-here = AO{ $count } up to this solve
+in-current-averages = Counts towards your current { $averages }
+copy = Copy
+retry = Retry scramble
+move-to-puzzle = Move to puzzle
+solve-moved = Solve moved to { $puzzle }
+solve-move-newer = The { $puzzle } records were saved by a newer version of Tesseract, so the solve can't be moved there
+copy-average = Copy average
+average-copied = AO{ $count } copied
+sharing = Sharing
+share-template = Copied average format
+share-template-description = Averages are copied with each time, the best and worst in parentheses, and their scrambles
+template-plain = Plain text
+template-markdown = Markdown
+virtual-cube = Virtual cube
+virtual-cube-hint = Turn the cube with the keyboard: I and K for R, J and F for U, H and G for F, D and E for L, S and L for D, W and O for B. The timer starts with the first turn.
+virtual-cube-solving = The timer stops when the cube is solved
+virtual-cube-unsupported = This scramble can't be shown on the virtual cube
+custom-scrambles = Custom scrambles
+custom-scrambles-description = Type or paste scrambles for the { $puzzle }, one per line. They are used in order before random scrambles carry on.
+custom-scrambles-placeholder = R U R' U' F2 D
+custom-scrambles-use = Use scrambles
+custom-scrambles-empty = Type at least one scramble
+custom-scrambles-invalid = Line { $line }, character { $position }: "{ $token }" is not a { $puzzle } move
+custom-scrambles-left = { $count ->
+    [one] 1 custom scramble left
+   *[other] { $count } custom scrambles left
+}
+custom-scrambles-clear = Back to random scrambles
+next-scramble = Next: { $scramble }
+overlay = Stream overlay
+overlay-enabled = Serve a stream overlay
+overlay-description = Add { $url } as a browser source in OBS to show the scramble, timer and averages. { $url }state.json and { $url }events give the same as JSON and Server-Sent Events.
+overlay-port = Port
+overlay-failed = The stream overlay couldn't start: { $error }
+sync = Sync
+sync-target = Sync solves
+sync-description = Keep solves the same on every device through a folder shared by a program such as Syncthing or Nextcloud, or a WebDAV server. Solves added on each device are kept, and solves removed on one are removed on all.
+sync-off = Off
+sync-folder = Shared folder
+sync-webdav = WebDAV
+sync-folder-path = Folder
+sync-folder-none = No folder chosen
+sync-folder-choose = Choose…
+sync-folder-empty = Choose a folder to sync through
+sync-folder-not-chosen = The folder couldn't be chosen: { $error }
+sync-url = Address
+sync-url-invalid = The address has to start with http:// or https://
+sync-username = Username
+sync-password = Password
+sync-now = Sync now
+sync-save = Save
+sync-running = Syncing…
+sync-last = Last synced { $time }
+sync-never = Not synced yet
+sync-failed = Syncing failed: { $error }
+sync-password-not-read = The WebDAV password couldn't be read from the keyring: { $error }
+sync-password-not-saved = The WebDAV password couldn't be saved in the keyring: { $error }
+trigger = Timer trigger
+trigger-description = The key, mouse button or gamepad button held to get ready and released to start, such as a foot pedal. Click to choose another, then press it.
+trigger-waiting = Press a key or button…
+trigger-space = Space
+trigger-mouse = Mouse { $button }
+trigger-gamepad = Gamepad { $button }
+sounds = Sounds
+sound-cues = Sound cues
+sound-cues-description = Beeps at 8 and 12 seconds of inspection, a tone when the timer is ready to start, and a fanfare for a new best
+sound-theme = Sound theme
+sound-theme-beeps = Beeps
+sound-theme-chimes = Chimes
+volume = Volume
+speak-results = Announce results
+speak-results-description = Read out each result once it's recorded, using speech-dispatcher
+goals = Goals
+goals-description = Set a result to beat or a number of solves to do, with an optional date to do it by. Singles and solve counts only include solves from when the goal is set, while averages are the current ones.
+goals-training = Goals track the puzzle's own record, so progress shows once training is turned off.
+goals-for = Goals for { $puzzle }
+goal-new = New goal
+goal-kind = Goal
+goal-kind-single = Single
+goal-kind-ao5 = AO5
+goal-kind-ao12 = AO12
+goal-kind-ao100 = AO100
+goal-kind-solves = Number of solves
+goal-target-result = Get under
+goal-target-solves = Solves
+goal-target-invalid = Enter a result such as 15.00, or a number of solves
+goal-deadline = By
+goal-deadline-description = A date such as 2026-12-01, or leave empty for no deadline
+goal-deadline-invalid = Enter a date in the future, such as 2026-12-01
+goal-add = Add goal
+goal-single = Sub-{ $target } single
+goal-average = Sub-{ $target } AO{ $count }
+goal-solves = { $count } solves
+goal-solves-done = { $count } of { $target }
+goal-due = Due { $date }
+goal-missed = Missed on { $date }
+goal-achieved-on = Achieved on { $date }
+goal-achieved = Goal achieved: { $goal }
+calendar = Practice calendar
+calendar-description = Days you practised { $puzzle }, darker for more solves. Choose a day to see how it went.
+streak-current = Current streak
+streak-longest = Longest streak
+streak-days = { $count ->
+    [one] { $count } day
+   *[other] { $count } days
+}
+days-practised = Days practised
+day-solves = Solves
+day-mean = Mean
+day-best = Best
+day-no-solves = No solves on this day
+performance = Performance
+performance-description = How your { $puzzle } means change through the day, across the week and as a practice block goes on. Shorter bars are faster. A break of more than { $minutes } minutes starts a new block.
+performance-hour = By hour of the day
+performance-weekday = By day of the week
+performance-position = By solve in a practice block
+performance-extremes = Fastest: { $fastest } ({ $fastest_mean }) · Slowest: { $slowest } ({ $slowest_mean })
+performance-too-few = Not enough solves yet, each needs at least { $count } to compare
+performance-bar = { $label }: { $mean }, { $count ->
+    [one] { $count } solve
+   *[other] { $count } solves
+}
+weekday-monday = Mon
+weekday-tuesday = Tue
+weekday-wednesday = Wed
+weekday-thursday = Thu
+weekday-friday = Fri
+weekday-saturday = Sat
+weekday-sunday = Sun
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! How results vary with the hour of the day, the day of the week and how far into a practice
+//! block a solve was, to show up warm-up and fatigue.
+
+use crate::record::Solve;
+use jiff::tz::TimeZone;
+
+/// A pause longer than this, in seconds, starts a new practice block.
+pub const BLOCK_GAP: u64 = 30 * 60;
+
+/// Solves further into a block than this are counted with the last position.
+pub const MAX_POSITION: usize = 30;
+
+/// Solves that fell into one hour, weekday or position.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bucket {
+    pub solves: usize,
+    /// Mean of the results that aren't DNFs.
+    pub mean: Option<u32>,
+}
+
+// Totals of results that aren't DNFs, to be turned into buckets
+#[derive(Debug, Clone, Copy, Default)]
+struct Sum {
+    solves: usize,
+    counted: usize,
+    total: u64,
+}
+impl Sum {
+    fn add(&mut self, solve: &Solve) {
+        self.solves += 1;
+        if !solve.dnf {
+            self.counted += 1;
+            self.total += solve.value() as u64;
+        }
+    }
+    fn bucket(&self) -> Bucket {
+        Bucket {
+            solves: self.solves,
+            mean: (self.counted > 0).then(|| (self.total / self.counted as u64) as u32),
+        }
+    }
+}
+
+// Each timed solve with its local time
+fn local_times<'a>(
+    solves: &'a [Solve],
+    tz: &'a TimeZone,
+) -> impl Iterator<Item = (&'a Solve, jiff::Zoned)> {
+    solves.iter().filter_map(move |solve| {
+        let timestamp = jiff::Timestamp::from_second(solve.timestamp? as i64).ok()?;
+        Some((solve, timestamp.to_zoned(tz.clone())))
+    })
+}
+
+/// Solves by the hour of the day they were done in, from midnight.
+pub fn by_hour(solves: &[Solve], tz: &TimeZone) -> [Bucket; 24] {
+    let mut sums = [Sum::default(); 24];
+    for (solve, time) in local_times(solves, tz) {
+        sums[time.hour() as usize].add(solve);
+    }
+    sums.map(|sum| sum.bucket())
+}
+
+/// Solves by the day of the week they were done on, from Monday.
+pub fn by_weekday(solves: &[Solve], tz: &TimeZone) -> [Bucket; 7] {
+    let mut sums = [Sum::default(); 7];
+    for (solve, time) in local_times(solves, tz) {
+        sums[time.weekday().to_monday_zero_offset() as usize].add(solve);
+    }
+    sums.map(|sum| sum.bucket())
+}
+
+/// Solves by their position in a practice block, from the first solve of a block. `solves`
+/// are newest first, as records keep them.
+pub fn by_position(solves: &[Solve]) -> Vec<Bucket> {
+    let mut sums = vec![Sum::default(); MAX_POSITION];
+    let mut position = 0;
+    let mut previous: Option<u64> = None;
+    for solve in solves.iter().rev() {
+        let Some(timestamp) = solve.timestamp else {
+            continue;
+        };
+        position = match previous {
+            Some(previous) if timestamp.saturating_sub(previous) <= BLOCK_GAP => position + 1,
+            _ => 0,
+        };
+        previous = Some(timestamp);
+        sums[position.min(MAX_POSITION - 1)].add(solve);
+    }
+    // Positions no block ever reached are left off
+    let reached = sums
+        .iter()
+        .rposition(|sum| sum.solves > 0)
+        .map_or(0, |i| i + 1);
+    sums[..reached].iter().map(Sum::bucket).collect()
+}
+
+/// Index of the buckets with the lowest and highest mean, skipping those with too few solves
+/// to say much.
+pub fn extremes(buckets: &[Bucket], min_solves: usize) -> Option<(usize, usize)> {
+    let means = buckets
+        .iter()
+        .enumerate()
+        .filter(|(_, bucket)| bucket.solves >= min_solves)
+        .filter_map(|(i, bucket)| Some((i, bucket.mean?)));
+    let fastest = means.clone().min_by_key(|&(_, mean)| mean)?.0;
+    let slowest = means.max_by_key(|&(_, mean)| mean)?.0;
+    Some((fastest, slowest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::solve;
+
+    // 5 January 2026, a Monday, at midnight UTC
+    const MONDAY: u64 = 1_767_571_200;
+
+    #[test]
+    fn hours_and_weekdays_use_local_time() {
+        let mut dnf = solve(1_000, MONDAY + 9 * 3600);
+        dnf.dnf = true;
+        let solves = vec![
+            dnf,
+            solve(12_000, MONDAY + 9 * 3600 + 60),
+            solve(14_000, MONDAY + 86_400 + 9 * 3600),
+        ];
+        let hours = by_hour(&solves, &TimeZone::UTC);
+        assert_eq!(
+            hours[9],
+            Bucket {
+                solves: 3,
+                mean: Some(13_000)
+            }
+        );
+        assert_eq!(hours[10], Bucket::default());
+
+        let weekdays = by_weekday(&solves, &TimeZone::UTC);
+        assert_eq!(weekdays[0].mean, Some(12_000));
+        assert_eq!(weekdays[1].mean, Some(14_000));
+    }
+
+    #[test]
+    fn blocks_restart_after_a_pause() {
+        // Newest first: a block of two, nearly three hours after a block of three
+        let solves = vec![
+            solve(10_000, MONDAY + 3 * 3600 + 60),
+            solve(11_000, MONDAY + 3 * 3600),
+            solve(16_000, MONDAY + 120),
+            solve(14_000, MONDAY + 60),
+            solve(18_000, MONDAY),
+        ];
+        let positions = by_position(&solves);
+        assert_eq!(positions.len(), 3);
+        assert_eq!(positions[0].mean, Some(14_500));
+        assert_eq!(positions[1].mean, Some(12_000));
+        assert_eq!(positions[2].solves, 1);
+        assert_eq!(extremes(&positions, 1), Some((1, 2)));
+        assert_eq!(extremes(&positions, 2), Some((1, 0)));
+    }
+}
@@ -1,27 +1,60 @@
 // SPDX-License-Identifier: GPL-3.0
 
+use crate::analysis::{self, Bucket};
+use crate::backup::{self, Backups, Reason};
+use crate::calendar;
+use crate::cube::{CubeState, Move};
+use crate::dbus::{self, Remote, Request};
 use crate::fl;
-use crate::record::{Cube, Record, Solve};
-use crate::timer::{Status, Timer, format_from_ms};
+use crate::fmc::{self, SolutionError, Warning};
+use crate::goal::{self, Goal, Kind};
+use crate::history::{Change, Edit, History};
+use crate::image;
+use crate::overlay;
+use crate::record::{self, Cube, Penalty, Record, Solve};
+use crate::scramble::{self, Queue};
+use crate::share::{self, Template};
+use crate::sound::{self, Cue};
+use crate::sync::{self, Target};
+use crate::timer::{self, Entry, Precision, Status, Timer};
+use crate::trainer::{self, Drill};
+use crate::training::{self, Subset};
+use crate::trigger::{self, Input};
+use crate::virtual_cube::{self, VirtualCube};
 use cosmic::app::context_drawer::{self, ContextDrawer};
-use cosmic::cosmic_config::{Config, ConfigGet, ConfigSet};
-use cosmic::iced::{self, Alignment, Border, Event, Length, Subscription, event, keyboard, time};
+use cosmic::cosmic_config::{self, Config, ConfigGet, ConfigSet};
+use cosmic::iced::widget::text_editor;
+use cosmic::iced::{
+    self, Alignment, Border, Event, Length, Subscription, event, keyboard, mouse, time, touch,
+};
 use cosmic::prelude::*;
 use cosmic::widget::{
-    self, Space, about, about::About, container, dropdown, menu, nav_bar, scrollable, settings,
+    self, Space, about,
+    about::About,
+    container, dropdown, menu, nav_bar, scrollable, settings,
+    toaster::{Toast, ToastId, Toasts},
 };
 use cube_scrambler::generate_scramble;
 use hrsw::Stopwatch;
 use std::collections::{HashMap, VecDeque};
-use std::time::Duration;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::watch;
 use tracing;
+use uuid::Uuid;
 
 const APP_ICON: &[u8] = include_bytes!("../resources/icons/hicolor/scalable/apps/icon.svg");
 
+// A solve can be split into at most this many timed phases, such as cross, F2L, OLL and PLL
+const MAX_PHASES: usize = 4;
+// Scrambles that can be stepped back through
+const MAX_PREVIOUS_SCRAMBLES: usize = 20;
+
 #[derive(Clone, Debug)]
 pub enum DialogPage {
     RemoveAllSolves,
-    RemoveSolve(usize),
+    RemoveSolve(Uuid),
+    RestoreBackup(usize),
 }
 
 pub struct AppModel {
@@ -32,13 +65,99 @@ pub struct AppModel {
     config: Config,
     state: Config,
     dialog_pages: VecDeque<DialogPage>,
-    space_pressed: bool,
+    trigger: Input,
+    // Waiting for the next input to become the trigger
+    binding_trigger: bool,
+    trigger_pressed: bool,
     current_cube: Cube,
     cube_options: Vec<Cube>,
     cube_options_labels: Vec<String>,
     current_scramble: Vec<String>,
+    custom_scrambles: VecDeque<Vec<String>>,
+    scramble_queue: Queue,
+    // Scrambles before the current one and ones stepped back from, each with its training case
+    previous_scrambles: Vec<(Vec<String>, Option<String>)>,
+    skipped_scrambles: Vec<(Vec<String>, Option<String>)>,
+    scramble_input: text_editor::Content,
+    scramble_error: Option<String>,
     timer: Timer,
+    // When the running timer started, in milliseconds since the Unix epoch
+    started_at: u64,
+    precision: Precision,
+    precision_options_labels: Vec<String>,
+    wca_rounding: bool,
+    inspection: bool,
+    // When inspection began, while it's underway
+    inspection_started: Option<Instant>,
+    // Inspection cues played so far
+    inspection_cues: usize,
+    // Penalty earned in inspection by the solve underway
+    penalty: Penalty,
+    phases: usize,
+    phase_options_labels: Vec<String>,
+    share_template: Template,
+    share_template_options_labels: Vec<String>,
+    selected_day: Option<jiff::civil::Date>,
+    goals: Vec<Goal>,
+    goal_kind: usize,
+    goal_kind_options_labels: Vec<String>,
+    goal_target_input: String,
+    goal_deadline_input: String,
+    goal_error: Option<String>,
+    sounds: bool,
+    sound_theme: sound::Theme,
+    sound_theme_options_labels: Vec<String>,
+    // From 0 to 100
+    volume: u8,
+    speak_results: bool,
+    // Whether speech-dispatcher can be reached, checked once at startup
+    can_speak: bool,
+    splits: Vec<u32>,
+    fmc_attempt: Option<fmc::Attempt>,
+    manual_entry: bool,
+    manual_input: String,
+    manual_error: bool,
+    virtual_cube: bool,
+    virtual_state: Option<VirtualCube>,
+    hide_timer: bool,
+    touch_pads: bool,
+    // Whether the left and right touch pads are held
+    pads_held: [bool; 2],
+    focus_mode: bool,
+    training: Option<Subset>,
+    training_cases: Vec<String>,
+    training_options_labels: Vec<String>,
+    current_case: Option<String>,
+    trainer: Option<Subset>,
+    trainer_options_labels: Vec<String>,
+    drill: Option<Drill>,
     record: Record,
+    history: History,
+    backups: Backups,
+    backup_due: bool,
+    record_read_only: bool,
+    record_error: Option<String>,
+    comment_input: String,
+    selected_solve: Option<Uuid>,
+    detail_comment: String,
+    remote: Option<Remote>,
+    overlay_enabled: bool,
+    overlay_port: u16,
+    overlay_port_input: String,
+    overlay: Option<watch::Sender<overlay::State>>,
+    /// Counts overlay servers started, so the result of one that was replaced can be ignored.
+    overlay_generation: u64,
+    sync_target: Target,
+    sync_kind: usize,
+    sync_kind_options_labels: Vec<String>,
+    sync_folder: Option<PathBuf>,
+    sync_url_input: String,
+    sync_username_input: String,
+    sync_password_input: String,
+    syncing: bool,
+    last_sync: Option<u64>,
+    sync_error: Option<String>,
+    toasts: Toasts<Message>,
     stopwatch: Stopwatch,
     about_page: About,
 }
@@ -47,17 +166,98 @@ pub struct AppModel {
 pub enum Message {
     ToggleContextPage(ContextPage),
     Rescramble,
+    PreviousScramble,
+    ScrambleGenerated(String, Vec<String>, Option<String>),
+    ScrambleEdit(text_editor::Action),
+    UseCustomScrambles,
+    ClearCustomScrambles,
     TimerTick,
-    SpacePressed,
-    SpaceReleased,
-    SpaceHeld,
+    TriggerInput(Input, bool, event::Status),
+    TriggerPressed,
+    TriggerReleased,
+    TriggerHeld,
+    BindTrigger,
     OpenUrl(String),
     CubeUpdate(usize),
     DialogCancel,
     DialogRemoveAllSolves,
-    DialogRemoveSolve(usize),
-    RemoveSolve(usize),
+    DialogRemoveSolve(Uuid),
+    RemoveSolve(Uuid),
     RemoveAllSolves,
+    FmcStart,
+    FmcTick,
+    FmcSolutionInput(String),
+    FmcSubmit,
+    FmcCancel,
+    ToggleManualEntry,
+    ManualInput(String),
+    ManualSubmit,
+    ToggleVirtualCube,
+    VirtualTurn(String),
+    ToggleTouchPads,
+    PadPressed(usize),
+    PadsReleased,
+    ToggleHideTimer,
+    ToggleFocusMode,
+    TrainingSubset(usize),
+    TrainingCase(String, bool),
+    TrainingSelectAll(bool),
+    ToggleTrainer,
+    TrainerSubset(usize),
+    TrainerRecognise,
+    TrainerAnswer(bool),
+    PrecisionUpdate(usize),
+    ToggleWcaRounding(bool),
+    ToggleInspection(bool),
+    InspectionTick,
+    PhasesUpdate(usize),
+    ShareTemplateUpdate(usize),
+    SelectDay(jiff::civil::Date),
+    GoalKindUpdate(usize),
+    GoalTargetInput(String),
+    GoalDeadlineInput(String),
+    AddGoal,
+    RemoveGoal(usize),
+    ToggleSounds(bool),
+    SoundThemeUpdate(usize),
+    VolumeUpdate(u8),
+    ToggleSpeakResults(bool),
+    Key(keyboard::Modifiers, keyboard::Key),
+    Undo,
+    Redo,
+    ToastUndo(ToastId),
+    CloseToast(ToastId),
+    SetPenalty(Uuid, Penalty),
+    CommentInput(String),
+    SaveComment(Uuid, String),
+    OpenSolve(Uuid),
+    DetailCommentInput(String),
+    CopySolve(Uuid),
+    CopyAverage(Uuid, usize),
+    RetrySolve(Uuid),
+    MoveSolve(Uuid, usize),
+    BackupTick,
+    BackupNow,
+    DialogRestoreBackup(usize),
+    RestoreBackup(usize),
+    DismissRecordError,
+    Remote(dbus::Event),
+    ToggleOverlay(bool),
+    OverlayPortInput(String),
+    OverlayPortSubmit,
+    OverlayStopped(u64, Result<(), String>),
+    SyncKindUpdate(usize),
+    ChooseSyncFolder,
+    SyncFolderChosen(Result<Option<PathBuf>, String>),
+    SyncUrlInput(String),
+    SyncUsernameInput(String),
+    SyncPasswordInput(String),
+    SaveSyncTarget,
+    SyncNow,
+    SyncFetched(Result<Vec<(String, sync::Shared)>, String>),
+    SyncPasswordLoaded(Result<String, String>),
+    SyncPasswordStored(Result<(), String>),
+    SyncDone(Result<(), String>),
 }
 
 impl cosmic::Application for AppModel {
@@ -82,57 +282,289 @@ impl cosmic::Application for AppModel {
         let nav = nav_bar::Model::default();
         let config = cosmic::cosmic_config::Config::new(Self::APP_ID, 1).unwrap();
         let state = cosmic::cosmic_config::Config::new_state(Self::APP_ID, 1).unwrap();
+        let backups = Backups::new(
+            cosmic::cosmic_config::Config::new(&format!("{}.Backups", Self::APP_ID), 1).unwrap(),
+        );
 
         // cube values
         let current_cube = state.get::<Cube>("current_cube").unwrap_or_default();
-        let cube_options = vec![
-            Cube::Two,
-            Cube::Three,
-            Cube::Four,
-            Cube::Five,
-            Cube::Six,
-            Cube::Seven,
-        ];
+        let cube_options = Cube::ALL.to_vec();
         let cube_options_labels: Vec<String> = cube_options.iter().map(|t| t.as_string()).collect();
 
-        // load record for selected cube
-        let record = config
-            .get::<Record>(current_cube.config_key())
+        // timer values
+        let precision = config.get::<Precision>("precision").unwrap_or_default();
+        let precision_options_labels: Vec<String> =
+            Precision::ALL.iter().map(|p| p.as_string()).collect();
+        let wca_rounding = config.get::<bool>("wca_rounding").unwrap_or_default();
+        let inspection = config.get::<bool>("inspection").unwrap_or_default();
+        let phases = config
+            .get::<usize>("phases")
+            .unwrap_or(1)
+            .clamp(1, MAX_PHASES);
+        let phase_options_labels: Vec<String> = (1..=MAX_PHASES).map(|n| n.to_string()).collect();
+
+        // stream overlay values
+        let overlay_enabled = config.get::<bool>("overlay").unwrap_or_default();
+        let overlay_port = config
+            .get::<u16>("overlay_port")
+            .unwrap_or(overlay::DEFAULT_PORT);
+
+        // sync values
+        let sync_target = config.get::<Target>("sync_target").unwrap_or_default();
+        let sync_kind_options_labels =
+            vec![fl!("sync-off"), fl!("sync-folder"), fl!("sync-webdav")];
+        let last_sync = config.get::<u64>("last_sync").ok();
+
+        // sharing values
+        let share_template = config.get::<Template>("share_template").unwrap_or_default();
+        let share_template_options_labels = vec![fl!("template-plain"), fl!("template-markdown")];
+
+        // goal values
+        let goals = config.get::<Vec<Goal>>("goals").unwrap_or_default();
+        let goal_kind_options_labels = vec![
+            fl!("goal-kind-single"),
+            fl!("goal-kind-ao5"),
+            fl!("goal-kind-ao12"),
+            fl!("goal-kind-ao100"),
+            fl!("goal-kind-solves"),
+        ];
+
+        // sound values
+        let sounds = config.get::<bool>("sounds").unwrap_or_default();
+        let sound_theme = config
+            .get::<sound::Theme>("sound_theme")
             .unwrap_or_default();
+        let sound_theme_options_labels = vec![fl!("sound-theme-beeps"), fl!("sound-theme-chimes")];
+        let volume = config.get::<u8>("volume").unwrap_or(70).min(100);
+        let speak_results = config.get::<bool>("speak_results").unwrap_or_default();
+
+        let trigger = config.get::<Input>("trigger").unwrap_or_default();
+        let manual_entry = config.get::<bool>("manual_entry").unwrap_or_default();
+        let virtual_cube = config.get::<bool>("virtual_cube").unwrap_or_default();
+        let hide_timer = config.get::<bool>("hide_timer").unwrap_or_default();
+        let touch_pads = config.get::<bool>("touch_pads").unwrap_or_default();
+        let focus_mode = config.get::<bool>("focus_mode").unwrap_or_default();
+
+        // training values
+        let training = config.get::<Option<Subset>>("training").unwrap_or_default();
+        let training_cases = config
+            .get::<Vec<String>>("training_cases")
+            .unwrap_or_else(|_| all_cases(training));
+        let training_options_labels: Vec<String> = std::iter::once(fl!("training-off"))
+            .chain(Subset::ALL.iter().map(|s| s.as_string()))
+            .collect();
+
+        // algorithm trainer values
+        let trainer = config.get::<Option<Subset>>("trainer").unwrap_or_default();
+        let trainer_options_labels: Vec<String> =
+            trainer::SUBSETS.iter().map(|s| s.as_string()).collect();
 
         let mut app = AppModel {
             core,
             context_page: ContextPage::default(),
             nav,
-            key_binds: HashMap::new(),
+            key_binds: key_binds(),
             config,
             state,
             dialog_pages: VecDeque::new(),
-            current_cube: current_cube.clone(),
+            current_cube,
             cube_options,
             cube_options_labels,
-            current_scramble: generate_scramble(None, Some(current_cube.as_string()))
-                .unwrap_or_default(),
+            current_scramble: vec![],
+            custom_scrambles: VecDeque::new(),
+            scramble_queue: Queue::default(),
+            previous_scrambles: vec![],
+            skipped_scrambles: vec![],
+            scramble_input: text_editor::Content::new(),
+            scramble_error: None,
             timer: Timer::default(),
-            space_pressed: false,
-            record,
+            started_at: 0,
+            precision,
+            precision_options_labels,
+            wca_rounding,
+            inspection,
+            inspection_started: None,
+            inspection_cues: 0,
+            penalty: Penalty::None,
+            phases,
+            phase_options_labels,
+            share_template,
+            share_template_options_labels,
+            selected_day: None,
+            goals,
+            goal_kind: 2,
+            goal_kind_options_labels,
+            goal_target_input: String::new(),
+            goal_deadline_input: String::new(),
+            goal_error: None,
+            sounds,
+            sound_theme,
+            sound_theme_options_labels,
+            volume,
+            speak_results,
+            can_speak: sound::can_speak(),
+            splits: vec![],
+            fmc_attempt: None,
+            manual_entry,
+            manual_input: String::new(),
+            manual_error: false,
+            virtual_cube,
+            virtual_state: None,
+            hide_timer,
+            touch_pads,
+            pads_held: [false; 2],
+            focus_mode,
+            training,
+            training_cases,
+            training_options_labels,
+            current_case: None,
+            trainer,
+            trainer_options_labels,
+            drill: None,
+            trigger,
+            binding_trigger: false,
+            trigger_pressed: false,
+            record: Record::default(),
+            history: History::default(),
+            backups,
+            backup_due: false,
+            record_read_only: false,
+            record_error: None,
+            comment_input: String::new(),
+            selected_solve: None,
+            detail_comment: String::new(),
+            remote: None,
+            overlay_enabled,
+            overlay_port,
+            overlay_port_input: overlay_port.to_string(),
+            overlay: None,
+            overlay_generation: 0,
+            sync_kind: match &sync_target {
+                Target::Off => 0,
+                Target::Folder(_) => 1,
+                Target::WebDav { .. } => 2,
+            },
+            sync_kind_options_labels,
+            sync_folder: match &sync_target {
+                Target::Folder(path) => Some(path.clone()),
+                _ => None,
+            },
+            sync_url_input: match &sync_target {
+                Target::WebDav { url, .. } => url.clone(),
+                _ => String::new(),
+            },
+            sync_username_input: match &sync_target {
+                Target::WebDav { username, .. } => username.clone(),
+                _ => String::new(),
+            },
+            sync_password_input: match &sync_target {
+                Target::WebDav { password, .. } => password.clone(),
+                _ => String::new(),
+            },
+            sync_target,
+            syncing: false,
+            last_sync,
+            sync_error: None,
+            toasts: Toasts::new(Message::CloseToast),
             stopwatch: Stopwatch::new(),
             about_page: build_about(),
         };
 
-        let command = app.update_title();
+        // load record for selected cube
+        app.load_record();
+        app.rescramble();
+        app.next_drill();
+        app.sync_comment();
+
+        let overlay = match app.overlay_enabled {
+            true => app.start_overlay(),
+            false => Task::none(),
+        };
+        let command = Task::batch([
+            app.update_title(),
+            app.fill_scramble_queue(),
+            overlay,
+            app.load_sync_password(),
+        ]);
 
         (app, command)
     }
 
     fn header_start(&self) -> Vec<Element<'_, Self::Message>> {
-        let menu_bar = menu::bar(vec![menu::Tree::with_children(
-            menu::root(fl!("view")).apply(Element::from),
-            menu::items(
-                &self.key_binds,
-                vec![menu::Item::Button(fl!("about"), None, MenuAction::About)],
+        let edit_item = |label, enabled, action| match enabled {
+            true => menu::Item::Button(label, None, action),
+            false => menu::Item::ButtonDisabled(label, None, action),
+        };
+        let menu_bar = menu::bar(vec![
+            menu::Tree::with_children(
+                menu::root(fl!("edit")).apply(Element::from),
+                menu::items(
+                    &self.key_binds,
+                    vec![
+                        edit_item(fl!("undo"), self.history.can_undo(), MenuAction::Undo),
+                        edit_item(fl!("redo"), self.history.can_redo(), MenuAction::Redo),
+                        menu::Item::Divider,
+                        menu::Item::Button(fl!("backups"), None, MenuAction::Backups),
+                    ],
+                ),
+            ),
+            menu::Tree::with_children(
+                menu::root(fl!("view")).apply(Element::from),
+                menu::items(
+                    &self.key_binds,
+                    vec![
+                        menu::Item::CheckBox(
+                            fl!("manual-entry"),
+                            None,
+                            self.manual_entry,
+                            MenuAction::ToggleManualEntry,
+                        ),
+                        menu::Item::CheckBox(
+                            fl!("virtual-cube"),
+                            None,
+                            self.virtual_cube,
+                            MenuAction::ToggleVirtualCube,
+                        ),
+                        menu::Item::CheckBox(
+                            fl!("touch-pads"),
+                            None,
+                            self.touch_pads,
+                            MenuAction::ToggleTouchPads,
+                        ),
+                        menu::Item::CheckBox(
+                            fl!("hide-timer"),
+                            None,
+                            self.hide_timer,
+                            MenuAction::ToggleHideTimer,
+                        ),
+                        menu::Item::CheckBox(
+                            fl!("focus-mode"),
+                            None,
+                            self.focus_mode,
+                            MenuAction::ToggleFocusMode,
+                        ),
+                        menu::Item::Divider,
+                        menu::Item::CheckBox(
+                            fl!("trainer"),
+                            None,
+                            self.trainer.is_some(),
+                            MenuAction::ToggleTrainer,
+                        ),
+                        menu::Item::Button(fl!("training"), None, MenuAction::Training),
+                        menu::Item::Button(
+                            fl!("custom-scrambles"),
+                            None,
+                            MenuAction::CustomScrambles,
+                        ),
+                        menu::Item::Button(fl!("calendar"), None, MenuAction::Calendar),
+                        menu::Item::Button(fl!("goals"), None, MenuAction::Goals),
+                        menu::Item::Button(fl!("performance"), None, MenuAction::Performance),
+                        menu::Item::Button(fl!("settings"), None, MenuAction::Settings),
+                        menu::Item::Button(fl!("about"), None, MenuAction::About),
+                    ],
+                ),
             ),
-        )]);
+        ]);
 
         vec![menu_bar.into()]
     }
@@ -151,6 +583,70 @@ impl cosmic::Application for AppModel {
                 actions: None,
                 footer: None,
             }),
+            ContextPage::Backups => Some(ContextDrawer {
+                title: Some(fl!("backups").into()),
+                content: self.backups_page(),
+                on_close: Message::ToggleContextPage(ContextPage::Backups),
+                header: None,
+                actions: None,
+                footer: None,
+            }),
+            ContextPage::Calendar => Some(ContextDrawer {
+                title: Some(fl!("calendar").into()),
+                content: self.calendar_page(),
+                on_close: Message::ToggleContextPage(ContextPage::Calendar),
+                header: None,
+                actions: None,
+                footer: None,
+            }),
+            ContextPage::Goals => Some(ContextDrawer {
+                title: Some(fl!("goals").into()),
+                content: self.goals_page(),
+                on_close: Message::ToggleContextPage(ContextPage::Goals),
+                header: None,
+                actions: None,
+                footer: None,
+            }),
+            ContextPage::Scrambles => Some(ContextDrawer {
+                title: Some(fl!("custom-scrambles").into()),
+                content: self.scrambles_page(),
+                on_close: Message::ToggleContextPage(ContextPage::Scrambles),
+                header: None,
+                actions: None,
+                footer: None,
+            }),
+            ContextPage::Settings => Some(ContextDrawer {
+                title: Some(fl!("settings").into()),
+                content: self.settings_page(),
+                on_close: Message::ToggleContextPage(ContextPage::Settings),
+                header: None,
+                actions: None,
+                footer: None,
+            }),
+            ContextPage::Performance => Some(ContextDrawer {
+                title: Some(fl!("performance").into()),
+                content: self.performance_page(),
+                on_close: Message::ToggleContextPage(ContextPage::Performance),
+                header: None,
+                actions: None,
+                footer: None,
+            }),
+            ContextPage::Solve => Some(ContextDrawer {
+                title: Some(fl!("solve").into()),
+                content: self.solve_page(),
+                on_close: Message::ToggleContextPage(ContextPage::Solve),
+                header: None,
+                actions: None,
+                footer: None,
+            }),
+            ContextPage::Training => Some(ContextDrawer {
+                title: Some(fl!("training").into()),
+                content: self.training_page(),
+                on_close: Message::ToggleContextPage(ContextPage::Training),
+                header: None,
+                actions: None,
+                footer: None,
+            }),
         }
     }
 
@@ -163,127 +659,237 @@ impl cosmic::Application for AppModel {
             active_theme.cosmic().space_l()
         };
 
+        // Focus mode keeps only the timer on screen while a solve is underway
+        if self.focus_mode && self.timer.status != Status::Stopped && self.trigger_timer_enabled() {
+            let mut content = widget::column([]).push(self.timer_view());
+            if self.touch_pads {
+                content = content
+                    .push(Space::new().height(padding))
+                    .push(self.touch_pads_view());
+            }
+            return container(content)
+                .center_x(Length::Fill)
+                .center_y(Length::Fill)
+                .padding(padding)
+                .into();
+        }
+
         // Start container
         let mut page_content = widget::column([])
             .padding(0.)
             .width(Length::Fill)
             .align_x(Alignment::Center);
 
-        // Cube header
-        // TODO: Make this fancier. Maybe an icon and integration with the picker
-        page_content = page_content.push(widget::text::title1(self.current_cube.as_string()));
-
-        // Cube picker
-        let selected_cube = self
-            .cube_options
-            .iter()
-            .position(|r| *r == self.current_cube)
-            .unwrap_or(1);
-
-        page_content = page_content
-            .push(
+        // Failed writes stay on screen until dismissed
+        if let Some(error) = &self.record_error {
+            let error_color = active_theme.cosmic().destructive_color();
+            page_content = page_content.push(
                 widget::row([])
-                    .push(dropdown(
-                        &self.cube_options_labels,
-                        Some(selected_cube),
-                        move |value| Message::CubeUpdate(value),
-                    ))
+                    .align_y(Alignment::Center)
                     .push(
-                        widget::button::icon(
-                            widget::icon::from_name("view-refresh-symbolic").size(100),
-                        )
-                        .on_press(Message::Rescramble),
+                        widget::text::body(error.clone())
+                            .class(cosmic::style::Text::Color(cosmic::iced::Color::from_rgb(
+                                error_color.red,
+                                error_color.green,
+                                error_color.blue,
+                            )))
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        widget::button::icon(widget::icon::from_name("window-close-symbolic"))
+                            .on_press(Message::DismissRecordError),
                     ),
-            )
-            .push(container(
-                widget::text::text(self.current_scramble.join(" ")).size(28),
-            ));
+            );
+        }
 
-        // Timer
-        let timer_status = self.timer.status.clone();
-        let divider_color = match timer_status {
-            Status::Hold => active_theme.cosmic().destructive_color(),
-            Status::Ready => active_theme.cosmic().success_color(),
-            _ => active_theme.cosmic().accent_color(),
-        };
-        page_content = page_content
-            .push(Space::new().height(padding))
-            .push(widget::divider::horizontal::default())
-            .push(
-                widget::text::text(self.timer.display())
-                    .size(140)
+        if self.trainer.is_some() {
+            page_content = page_content
+                .push(widget::text::title1(fl!("trainer")))
+                .push(self.trainer_view(padding));
+        } else {
+            // Cube header
+            // TODO: Make this fancier. Maybe an icon and integration with the picker
+            page_content = page_content.push(widget::text::title1(self.current_cube.as_string()));
+            if let Some(subset) = self.training_subset() {
+                page_content = page_content.push(widget::text::title4(fl!(
+                    "training-subtitle",
+                    subset = subset.as_string()
+                )));
+            }
+
+            // Cube picker
+            let selected_cube = self
+                .cube_options
+                .iter()
+                .position(|r| *r == self.current_cube)
+                .unwrap_or(1);
+
+            page_content = page_content
+                .push(
+                    widget::row([])
+                        .push(dropdown(
+                            &self.cube_options_labels,
+                            Some(selected_cube),
+                            move |value| Message::CubeUpdate(value),
+                        ))
+                        .push(
+                            widget::button::icon(
+                                widget::icon::from_name("view-refresh-symbolic").size(100),
+                            )
+                            .on_press_maybe(
+                                self.fmc_attempt.is_none().then_some(Message::Rescramble),
+                            ),
+                        )
+                        .push(
+                            widget::button::icon(
+                                widget::icon::from_name("go-previous-symbolic").size(100),
+                            )
+                            .on_press_maybe(
+                                (self.fmc_attempt.is_none() && !self.previous_scrambles.is_empty())
+                                    .then_some(Message::PreviousScramble),
+                            ),
+                        ),
+                )
+                .push(container(
+                    widget::text::text(self.current_scramble.join(" ")).size(28),
+                ));
+            if let Some(subset) = self.training_subset()
+                && !self.training_cases_selected(subset)
+            {
+                let error_color = active_theme.cosmic().destructive_color();
+                page_content =
+                    page_content.push(widget::text::body(fl!("training-no-cases")).class(
+                        cosmic::style::Text::Color(cosmic::iced::Color::from_rgb(
+                            error_color.red,
+                            error_color.green,
+                            error_color.blue,
+                        )),
+                    ));
+            }
+            if self.fmc_attempt.is_none()
+                && let Some(next) = self.next_scramble()
+            {
+                page_content = page_content.push(widget::text::caption(fl!(
+                    "next-scramble",
+                    scramble = next.join(" ")
+                )));
+            }
+
+            // Pasted scrambles still to come
+            if !self.custom_scrambles.is_empty() {
+                page_content = page_content.push(
+                    widget::row([])
+                        .spacing(active_theme.cosmic().space_s())
+                        .align_y(Alignment::Center)
+                        .push(widget::text::caption(fl!(
+                            "custom-scrambles-left",
+                            count = self.custom_scrambles.len()
+                        )))
+                        .push(
+                            widget::button::text(fl!("custom-scrambles-clear"))
+                                .on_press(Message::ClearCustomScrambles),
+                        ),
+                );
+            }
+
+            if self.current_cube == Cube::ThreeFmc {
+                page_content = page_content.push(self.fmc_view(padding));
+            } else if self.manual_entry {
+                page_content = page_content.push(self.manual_entry_view(padding));
+            } else if self.virtual_cube {
+                page_content = page_content.push(self.virtual_cube_view(padding));
+            } else {
+                // Timer
+                page_content = page_content
+                    .push(Space::new().height(padding))
+                    .push(widget::divider::horizontal::default())
+                    .push(self.timer_view());
+
+                // Hint
+                page_content = page_content.push(Space::new().height(padding)).push(
+                    widget::text::text(match (self.touch_pads, &self.timer.status) {
+                        (true, Status::Running) => fl!("touch-pads-stop"),
+                        (true, Status::Stopped) if self.inspection => fl!("touch-pads-inspect"),
+                        (true, _) => fl!("touch-pads-start"),
+                        (false, Status::Running) => {
+                            fl!("tap-to-stop", trigger = self.trigger_label())
+                        }
+                        (false, Status::Stopped) if self.inspection => {
+                            fl!("tap-to-inspect", trigger = self.trigger_label())
+                        }
+                        (false, _) => fl!("hold-to-start", trigger = self.trigger_label()),
+                    })
+                    .size(16)
                     .width(Length::Fill)
                     .align_x(Alignment::Center),
-            )
-            .push(
-                container("")
-                    .height(13)
-                    .width(150)
-                    .style(move |_| container::Style {
-                        background: Some(iced::Background::Color(cosmic::iced::Color::from_rgb(
-                            divider_color.red,
-                            divider_color.green,
-                            divider_color.blue,
-                        ))),
-                        border: Border {
-                            radius: 20.into(),
-                            width: 0.0,
-                            color: iced::Color::TRANSPARENT,
-                        },
-                        ..Default::default()
-                    }),
-            );
+                );
+                if self.touch_pads {
+                    page_content = page_content
+                        .push(Space::new().height(padding))
+                        .push(self.touch_pads_view());
+                }
+            }
 
-        // Hint
-        page_content = page_content.push(Space::new().height(padding)).push(
-            widget::text::text(match self.timer.status {
-                Status::Running => fl!("tap-space-to-stop"),
-                _ => fl!("hold-space-to-start"),
-            })
-            .size(16)
-            .width(Length::Fill)
-            .align_x(Alignment::Center),
-        );
+            // Penalty and comment for the last solve
+            if let Some(latest) = self.record.solves.first()
+                && self.timer.status == Status::Stopped
+                && self.current_cube != Cube::ThreeFmc
+            {
+                page_content = page_content
+                    .push(Space::new().height(padding))
+                    .push(self.last_solve_view(latest.id));
+            }
+        }
 
         // Record
-        if !self.record.solves.is_empty() {
+        if let Some(latest) = self.record.solves.first() {
+            let cube = self.record_cube();
             let mut solve_list = settings::section();
             let ao5_label: String = String::from("AO5: ");
             let ao12_label: String = String::from("AO12: ");
             let ao100_label: String = String::from("AO100: ");
             let ao5_time = match self.record.ao5 {
-                Some(ms) => format_from_ms(ms),
+                Some(value) => cube.format_result(value, self.precision),
                 _ => String::from("N/A"),
             };
             let ao12_time = match self.record.ao12 {
-                Some(ms) => format_from_ms(ms),
+                Some(value) => cube.format_result(value, self.precision),
                 _ => String::from("N/A"),
             };
             let ao100_time = match self.record.ao100 {
-                Some(ms) => format_from_ms(ms),
+                Some(value) => cube.format_result(value, self.precision),
                 _ => String::from("N/A"),
             };
 
-            // Averages
+            // Averages, copied as text for sharing when clicked
             solve_list = solve_list.add(
                 widget::row([])
                     .push(
-                        widget::text::title4(ao5_label + &ao5_time)
-                            .size(15)
-                            .width(Length::Fill)
-                            .align_x(Alignment::Center),
+                        widget::mouse_area(
+                            widget::text::title4(ao5_label + &ao5_time)
+                                .size(15)
+                                .width(Length::Fill)
+                                .align_x(Alignment::Center),
+                        )
+                        .on_press(Message::CopyAverage(latest.id, 5)),
                     )
                     .push(
-                        widget::text::title4(ao12_label + &ao12_time)
-                            .size(15)
-                            .width(Length::Fill)
-                            .align_x(Alignment::Center),
+                        widget::mouse_area(
+                            widget::text::title4(ao12_label + &ao12_time)
+                                .size(15)
+                                .width(Length::Fill)
+                                .align_x(Alignment::Center),
+                        )
+                        .on_press(Message::CopyAverage(latest.id, 12)),
                     )
                     .push(
-                        widget::text::title4(ao100_label + &ao100_time)
-                            .size(15)
-                            .width(Length::Fill)
-                            .align_x(Alignment::Center),
+                        widget::mouse_area(
+                            widget::text::title4(ao100_label + &ao100_time)
+                                .size(15)
+                                .width(Length::Fill)
+                                .align_x(Alignment::Center),
+                        )
+                        .on_press(Message::CopyAverage(latest.id, 100)),
                     )
                     .push(container(
                         widget::button::icon(
@@ -294,34 +900,70 @@ impl cosmic::Application for AppModel {
                     )),
             );
 
+            // Per-case averages
+            if self.training_subset().is_some() || self.trainer.is_some() {
+                for (case, mean, count) in self.record.case_means() {
+                    solve_list = solve_list.add(
+                        widget::row([])
+                            .push(widget::text::body(case).width(Length::Fill))
+                            .push(
+                                widget::text::body(fl!("case-solves", count = count))
+                                    .width(Length::Fill),
+                            )
+                            .push(widget::text::body(cube.format_result(mean, self.precision))),
+                    );
+                }
+            }
+
             // Solves
-            let mut solve_i = 0;
             for solve in &self.record.solves {
                 solve_list = solve_list.add(
                     widget::row([])
                         .push(
-                            container(
-                                widget::text::body(format!("{}", solve.scramble.join(" ")))
-                                    .size(16)
+                            widget::mouse_area(
+                                widget::row([])
+                                    .push(
+                                        container({
+                                            let mut details =
+                                                widget::column([]).width(Length::Fill).push(
+                                                    widget::text::body(match &solve.case {
+                                                        Some(case) => {
+                                                            format!(
+                                                                "{case} · {}",
+                                                                solve.scramble.join(" ")
+                                                            )
+                                                        }
+                                                        None => solve.scramble.join(" "),
+                                                    })
+                                                    .size(16),
+                                                );
+                                            if let Some(comment) = &solve.comment {
+                                                details = details
+                                                    .push(widget::text::caption(comment.clone()));
+                                            }
+                                            details
+                                        })
+                                        .padding(active_theme.cosmic().space_s())
+                                        .align_y(Alignment::Center),
+                                    )
+                                    .push(
+                                        container(
+                                            widget::text::body(solve.result(self.precision))
+                                                .size(22)
+                                                .align_x(Alignment::Center),
+                                        )
+                                        .padding(active_theme.cosmic().space_s()),
+                                    )
                                     .width(Length::Fill),
                             )
-                            .padding(active_theme.cosmic().space_s())
-                            .align_y(Alignment::Center),
-                        )
-                        .push(
-                            container(
-                                widget::text::body(format!("{}", solve.time()))
-                                    .size(22)
-                                    .align_x(Alignment::Center),
-                            )
-                            .padding(active_theme.cosmic().space_s()),
+                            .on_press(Message::OpenSolve(solve.id)),
                         )
                         .push(
                             container(
                                 widget::button::icon(
                                     widget::icon::from_name("edit-delete-symbolic").size(100),
                                 )
-                                .on_press(Message::DialogRemoveSolve(solve_i)),
+                                .on_press(Message::DialogRemoveSolve(solve.id)),
                             )
                             .padding([
                                 ((active_theme.cosmic().space_s() / 2) + 2),
@@ -331,7 +973,6 @@ impl cosmic::Application for AppModel {
                             ]),
                         ),
                 );
-                solve_i += 1;
             }
 
             page_content = page_content
@@ -348,35 +989,76 @@ impl cosmic::Application for AppModel {
             .padding(padding);
 
         // Display
-        let content: Element<_> = scrollable(page_container).into();
+        let content: Element<_> = widget::toaster(&self.toasts, scrollable(page_container));
 
         content
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
         Subscription::batch(vec![
-            event::listen_with(|event, _status, _window_id| match event {
-                Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => match key.as_ref() {
-                    cosmic::iced::keyboard::Key::Character(" ") => Some(Message::SpacePressed),
-                    _ => None,
-                },
-                Event::Keyboard(keyboard::Event::KeyReleased { key, .. }) => match key.as_ref() {
-                    cosmic::iced::keyboard::Key::Character(" ") => Some(Message::SpaceReleased),
-                    _ => None,
-                },
-                // TODO: Add mouse / touch bindings
+            event::listen_with(|event, status, _window_id| match event {
+                Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                    if modifiers.control() && status == event::Status::Ignored {
+                        Some(Message::Key(modifiers, key))
+                    } else {
+                        trigger::key(&key).map(|input| Message::TriggerInput(input, true, status))
+                    }
+                }
+                Event::Keyboard(keyboard::Event::KeyReleased { key, .. }) => {
+                    trigger::key(&key).map(|input| Message::TriggerInput(input, false, status))
+                }
+                // Clicks on buttons and the like are theirs alone
+                Event::Mouse(mouse::Event::ButtonPressed(button))
+                    if status == event::Status::Ignored =>
+                {
+                    Some(Message::TriggerInput(trigger::mouse(button), true, status))
+                }
+                Event::Mouse(mouse::Event::ButtonReleased(button)) => {
+                    Some(Message::TriggerInput(trigger::mouse(button), false, status))
+                }
                 _ => None,
             }),
+            match self.trigger.is_gamepad() || self.binding_trigger {
+                true => Subscription::run(trigger::gamepad_events).map(|(input, pressed)| {
+                    Message::TriggerInput(input, pressed, event::Status::Ignored)
+                }),
+                false => Subscription::none(),
+            },
+            match self.touch_pads {
+                // A pad only hears of a finger lifted over it, and fingers slide off, so any
+                // lift lets go of both
+                true => event::listen_with(|event, _status, _window_id| match event {
+                    Event::Touch(
+                        touch::Event::FingerLifted { .. } | touch::Event::FingerLost { .. },
+                    )
+                    | Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                        Some(Message::PadsReleased)
+                    }
+                    _ => None,
+                }),
+                false => Subscription::none(),
+            },
             match self.timer.status {
-                Status::Running => {
-                    time::every(Duration::from_millis(100)).map(|_| Message::TimerTick)
+                Status::Running => time::every(self.precision.tick()).map(|_| Message::TimerTick),
+                _ if self.inspection_started.is_some() => {
+                    time::every(Duration::from_millis(100)).map(|_| Message::InspectionTick)
                 }
                 _ => Subscription::none(),
             },
-            match self.space_pressed {
-                true => time::every(Duration::from_millis(500)).map(|_| Message::SpaceHeld),
+            match self.trigger_pressed {
+                true => time::every(Duration::from_millis(500)).map(|_| Message::TriggerHeld),
+                _ => Subscription::none(),
+            },
+            match self.fmc_attempt {
+                Some(_) => time::every(Duration::from_secs(1)).map(|_| Message::FmcTick),
                 _ => Subscription::none(),
             },
+            time::every(backup::INTERVAL).map(|_| Message::BackupTick),
+            match self.sync_target {
+                Target::Off => Subscription::none(),
+                _ => time::every(sync::INTERVAL).map(|_| Message::SyncNow),
+            },
+            Subscription::run(dbus::serve).map(Message::Remote),
         ])
     }
 
@@ -384,10 +1066,10 @@ impl cosmic::Application for AppModel {
         let dialog_page = self.dialog_pages.front()?;
 
         let dialog = match dialog_page {
-            DialogPage::RemoveSolve(i) => widget::dialog()
+            DialogPage::RemoveSolve(id) => widget::dialog()
                 .title(fl!("remove-solve"))
                 .primary_action(
-                    widget::button::destructive(fl!("remove")).on_press(Message::RemoveSolve(*i)),
+                    widget::button::destructive(fl!("remove")).on_press(Message::RemoveSolve(*id)),
                 )
                 .secondary_action(
                     widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
@@ -403,6 +1085,18 @@ impl cosmic::Application for AppModel {
                     widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
                 )
                 .apply(Element::from),
+
+            DialogPage::RestoreBackup(slot) => widget::dialog()
+                .title(fl!("restore-backup"))
+                .body(fl!("restore-backup-description"))
+                .primary_action(
+                    widget::button::suggested(fl!("restore"))
+                        .on_press(Message::RestoreBackup(*slot)),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel")).on_press(Message::DialogCancel),
+                )
+                .apply(Element::from),
         };
 
         Some(dialog)
@@ -428,8 +1122,8 @@ impl cosmic::Application for AppModel {
                 self.dialog_pages.pop_front();
             }
 
-            Message::DialogRemoveSolve(i) => {
-                self.dialog_pages.push_front(DialogPage::RemoveSolve(i));
+            Message::DialogRemoveSolve(id) => {
+                self.dialog_pages.push_front(DialogPage::RemoveSolve(id));
             }
 
             Message::DialogRemoveAllSolves => {
@@ -438,117 +1132,2790 @@ impl cosmic::Application for AppModel {
 
             // TODO: refactor all this
             Message::TimerTick => {
-                self.timer.time = self.stopwatch.elapsed().as_millis() as u32;
-            }
-            Message::SpacePressed => {
-                self.space_pressed = true;
-                if self.timer.status == Status::Running {
-                    self.timer.time = self.stopwatch.elapsed().as_millis() as u32;
-                    let solve = Solve::new(self.timer.time, &self.current_scramble);
-                    self.timer.status = Status::Stopped;
-                    self.record.add_solve(solve);
-                    self.save_record();
-                    self.rescramble();
-                } else if self.timer.status == Status::Stopped {
+                self.timer.time = timer::ms_from_duration(self.stopwatch.elapsed());
+            }
+            Message::TriggerInput(input, pressed, status) => {
+                if self.binding_trigger {
+                    if pressed {
+                        self.binding_trigger = false;
+                        self.trigger = input;
+                        let _ = self.config.set("trigger", &self.trigger);
+                    }
+                } else if input == self.trigger
+                    // Keys typed into a text input belong to it, as clicks on buttons do, but a
+                    // release always counts so the timer is never left held
+                    && (!pressed || status == event::Status::Ignored)
+                {
+                    return self.update(match pressed {
+                        true => Message::TriggerPressed,
+                        false => Message::TriggerReleased,
+                    });
+                } else if let Input::Key(key) = input
+                    && pressed
+                    && status == event::Status::Ignored
+                {
+                    return self.update(Message::VirtualTurn(key));
+                }
+            }
+            Message::BindTrigger => {
+                self.binding_trigger = !self.binding_trigger;
+            }
+            Message::TriggerPressed if self.trainer.is_some() => {
+                if let Some(drill) = self.drill.as_mut() {
+                    drill.recognise();
+                }
+            }
+            // Typed results must not be interrupted by the space bar driving the timer
+            Message::TriggerPressed | Message::TriggerReleased | Message::TriggerHeld
+                if !self.trigger_timer_enabled() => {}
+            Message::TriggerPressed => {
+                // A held key repeats, which must not end more than one phase
+                let repeated = std::mem::replace(&mut self.trigger_pressed, true);
+                if self.timer.status == Status::Running && !repeated {
+                    let elapsed = timer::ms_from_duration(self.stopwatch.elapsed());
+                    if self.splits.len() + 1 < self.phases {
+                        // Every phase but the last ends in a split
+                        self.splits.push(elapsed);
+                    } else {
+                        self.finish_solve(elapsed, None);
+                    }
+                } else if self.timer.status == Status::Stopped && self.inspection {
+                    // Inspection starts on the first press, and the solve on the next
+                    if !repeated {
+                        self.inspection_started = Some(Instant::now());
+                        self.inspection_cues = 0;
+                        self.timer.status = Status::Inspecting;
+                    }
+                } else if self.timer.status == Status::Stopped
+                    || self.timer.status == Status::Inspecting && !repeated
+                {
                     self.timer.status = Status::Hold;
                 }
             }
-            Message::SpaceReleased => {
-                self.space_pressed = false;
-                if self.timer.status == Status::Ready {
-                    self.timer.time = 0;
-                    self.stopwatch.reset_and_start();
-                    self.timer.status = Status::Running;
-                } else {
-                    self.timer.status = Status::Stopped;
-                    self.stopwatch.stop();
+            Message::TriggerReleased => {
+                self.trigger_pressed = false;
+                match self.timer.status {
+                    Status::Ready => self.start_timer(),
+                    Status::Running => {}
+                    // Letting go too early goes back to inspecting, if that's underway
+                    _ if self.inspection_started.is_some() => {
+                        self.timer.status = Status::Inspecting;
+                    }
+                    _ => {
+                        self.timer.status = Status::Stopped;
+                        self.stopwatch.stop();
+                    }
                 }
             }
-            Message::SpaceHeld => {
+            Message::TriggerHeld => {
                 if self.timer.status == Status::Hold {
                     self.timer.status = Status::Ready;
+                    self.play(Cue::Ready);
                 }
             }
             Message::CubeUpdate(uid) => {
+                self.fmc_attempt = None;
+                // Pasted scrambles are for the puzzle they were checked against
+                self.custom_scrambles.clear();
                 self.current_cube = self.cube_options[uid].clone();
-                self.record = self
-                    .config
-                    .get::<Record>(self.current_cube.config_key())
-                    .unwrap_or_default();
+                self.load_record();
                 let _ = self.state.set("current_cube", &self.current_cube);
                 self.rescramble();
+                self.previous_scrambles.clear();
+                self.skipped_scrambles.clear();
             }
             Message::Rescramble => {
                 self.rescramble();
             }
-            Message::RemoveSolve(uid) => {
-                self.record.solves.remove(uid);
-                self.save_record();
+            Message::PreviousScramble => {
+                if let Some((scramble, case)) = self.previous_scrambles.pop() {
+                    let current = std::mem::replace(&mut self.current_scramble, scramble);
+                    let current_case = std::mem::replace(&mut self.current_case, case);
+                    self.skipped_scrambles.push((current, current_case));
+                    self.reset_virtual_cube();
+                }
+            }
+            Message::ScrambleGenerated(scramble_type, scramble, case) => {
+                self.scramble_queue.add(scramble_type, scramble, case);
+            }
+            Message::ScrambleEdit(action) => {
+                self.scramble_input.perform(action);
+                self.scramble_error = None;
+            }
+            Message::UseCustomScrambles => {
+                match scramble::parse_list(&self.scramble_input.text(), self.current_cube.size()) {
+                    Ok(scrambles) if scrambles.is_empty() => {
+                        self.scramble_error = Some(fl!("custom-scrambles-empty"));
+                    }
+                    Ok(scrambles) => {
+                        self.custom_scrambles = scrambles.into();
+                        self.skipped_scrambles.clear();
+                        self.scramble_input = text_editor::Content::new();
+                        self.fmc_attempt = None;
+                        self.core.window.show_context = false;
+                        self.rescramble();
+                    }
+                    Err(err) => {
+                        self.scramble_error = Some(fl!(
+                            "custom-scrambles-invalid",
+                            line = err.line,
+                            token = err.error.token,
+                            position = err.error.position + 1,
+                            puzzle = self.current_cube.as_string()
+                        ));
+                    }
+                }
+            }
+            Message::ClearCustomScrambles => {
+                self.custom_scrambles.clear();
+                self.rescramble();
+            }
+            Message::RemoveSolve(id) => {
+                // It may have gone since the dialog opened, such as by undoing or syncing
+                if self.record.solve(id).is_none() {
+                    self.dialog_pages.pop_front();
+                    return Task::none();
+                }
+                // Undo covers a single solve, and a snapshot each time would soon push the
+                // scheduled ones out of the ring
+                self.edit_record(|record| record.remove_solve(id));
+                if self.selected_solve == Some(id) {
+                    self.close_solve();
+                }
                 self.dialog_pages.pop_front();
+                return self.undo_toast(fl!("solve-removed"));
             }
             Message::RemoveAllSolves => {
-                self.record.solves = vec![];
-                self.save_record();
+                self.backup(Reason::BeforeClear);
+                self.edit_record(|record| record.solves = vec![]);
+                self.close_solve();
                 self.dialog_pages.pop_front();
+                return self.undo_toast(fl!("solves-removed"));
             }
-        }
-        Task::none()
-    }
-
-    fn on_nav_select(&mut self, id: nav_bar::Id) -> Task<cosmic::Action<Self::Message>> {
-        self.nav.activate(id);
-        self.update_title()
-    }
-}
-
-impl AppModel {
-    pub fn update_title(&mut self) -> Task<cosmic::Action<Message>> {
-        let mut window_title = fl!("app-title");
-
-        if let Some(page) = self.nav.text(self.nav.active()) {
-            window_title.push_str(" — ");
-            window_title.push_str(page);
-        }
-
-        if let Some(id) = self.core.main_window_id() {
-            self.set_window_title(window_title, id)
-        } else {
-            Task::none()
-        }
-    }
-
-    fn rescramble(&mut self) {
-        self.current_scramble =
-            generate_scramble(None, Some(self.current_cube.as_string())).unwrap_or_default();
-    }
-    fn save_record(&mut self) {
-        let _ = self
-            .config
-            .set(self.current_cube.config_key(), &self.record);
-    }
-}
-
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
-pub enum ContextPage {
-    #[default]
-    About,
-}
-
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub enum MenuAction {
-    About,
-}
-
-impl menu::action::MenuAction for MenuAction {
-    type Message = Message;
+            Message::FmcStart => {
+                self.fmc_attempt = Some(fmc::Attempt::new());
+            }
+            Message::FmcTick => {
+                if self
+                    .fmc_attempt
+                    .as_ref()
+                    .is_some_and(|attempt| attempt.expired())
+                {
+                    self.fmc_attempt = None;
+                    let mut solve =
+                        Solve::new(fmc::TIME_LIMIT.as_millis() as u32, &self.current_scramble);
+                    solve.dnf = true;
+                    self.edit_record(|record| record.add_solve(solve));
+                    self.rescramble();
+                }
+            }
+            Message::FmcSolutionInput(solution) => {
+                if let Some(attempt) = self.fmc_attempt.as_mut() {
+                    attempt.solution = solution;
+                    attempt.error = None;
+                }
+            }
+            Message::FmcSubmit => {
+                if let Some(attempt) = self.fmc_attempt.as_mut() {
+                    match fmc::check_solution(&self.current_scramble, &attempt.solution) {
+                        Ok(moves) => {
+                            let mut solve = Solve::new(
+                                timer::ms_from_duration(attempt.elapsed()),
+                                &self.current_scramble,
+                            );
+                            solve.moves = Some(moves);
+                            solve.solution = Some(attempt.solution.trim().to_string());
+                            self.fmc_attempt = None;
+                            self.edit_record(|record| record.add_solve(solve));
+                            self.rescramble();
+                        }
+                        Err(err) => attempt.error = Some(err),
+                    }
+                }
+            }
+            Message::FmcCancel => {
+                // Giving up counts as a DNF, and a scramble that has been studied isn't reused
+                if let Some(attempt) = self.fmc_attempt.take() {
+                    let mut solve = Solve::new(
+                        timer::ms_from_duration(attempt.elapsed()),
+                        &self.current_scramble,
+                    );
+                    solve.dnf = true;
+                    self.edit_record(|record| record.add_solve(solve));
+                    self.rescramble();
+                }
+            }
+            Message::ToggleManualEntry => {
+                self.manual_entry = !self.manual_entry;
+                self.manual_input.clear();
+                self.manual_error = false;
+                self.trigger_pressed = false;
+                self.timer = Timer::default();
+                self.inspection_started = None;
+                self.splits.clear();
+                self.stopwatch.stop();
+                let _ = self.config.set("manual_entry", self.manual_entry);
+            }
+            Message::ToggleVirtualCube => {
+                self.virtual_cube = !self.virtual_cube;
+                self.timer = Timer::default();
+                self.inspection_started = None;
+                self.splits.clear();
+                self.stopwatch.stop();
+                self.reset_virtual_cube();
+                let _ = self.config.set("virtual_cube", self.virtual_cube);
+            }
+            Message::VirtualTurn(key) => {
+                if !self.virtual_cube_enabled() {
+                    return Task::none();
+                }
+                // Keys for moves the puzzle is too small for, such as slices on a 2x2x2, do nothing
+                let size = self.current_cube.size();
+                let (Some(mv), Some(cube)) = (
+                    virtual_cube::key_move(&key).filter(|mv| mv.fits(size)),
+                    self.virtual_state.as_mut(),
+                ) else {
+                    return Task::none();
+                };
+                cube.turn(mv);
 
-    fn message(&self) -> Self::Message {
-        match self {
+                // Rotating to inspect the scramble doesn't start the solve, any turn does
+                if self.timer.status == Status::Stopped && !matches!(mv, Move::Rotation { .. }) {
+                    self.start_timer();
+                } else if self.timer.status == Status::Running && cube.is_solved() {
+                    let solution = cube.solution();
+                    let elapsed = timer::ms_from_duration(self.stopwatch.elapsed());
+                    self.stopwatch.stop();
+                    self.finish_solve(elapsed, Some(solution));
+                }
+            }
+            Message::ToggleTouchPads => {
+                self.touch_pads = !self.touch_pads;
+                self.pads_held = [false; 2];
+                let _ = self.config.set("touch_pads", self.touch_pads);
+            }
+            // Like a Stackmat, the pads act as one trigger that is only down while both are
+            Message::PadPressed(pad) => {
+                self.pads_held[pad] = true;
+                if self.pads_held == [true, true] {
+                    return self.update(Message::TriggerPressed);
+                }
+            }
+            Message::PadsReleased => {
+                let both_held = self.pads_held == [true, true];
+                self.pads_held = [false; 2];
+                if both_held {
+                    return self.update(Message::TriggerReleased);
+                }
+            }
+            Message::ToggleHideTimer => {
+                self.hide_timer = !self.hide_timer;
+                let _ = self.config.set("hide_timer", self.hide_timer);
+            }
+            Message::ToggleFocusMode => {
+                self.focus_mode = !self.focus_mode;
+                let _ = self.config.set("focus_mode", self.focus_mode);
+            }
+            Message::TrainingSubset(uid) => {
+                // The first option turns training off
+                self.training = uid.checked_sub(1).map(|i| Subset::ALL[i]);
+                self.training_cases = all_cases(self.training);
+                let _ = self.config.set("training", self.training);
+                let _ = self.config.set("training_cases", &self.training_cases);
+                self.load_record();
+                self.rescramble();
+            }
+            Message::TrainingCase(case, selected) => {
+                self.training_cases.retain(|c| *c != case);
+                if selected {
+                    self.training_cases.push(case);
+                }
+                let _ = self.config.set("training_cases", &self.training_cases);
+                if !selected && self.current_case.is_some() {
+                    self.rescramble();
+                }
+            }
+            Message::TrainingSelectAll(selected) => {
+                self.training_cases = match selected {
+                    true => all_cases(self.training),
+                    false => vec![],
+                };
+                let _ = self.config.set("training_cases", &self.training_cases);
+                self.rescramble();
+            }
+            Message::ToggleTrainer => {
+                self.trainer = match self.trainer {
+                    Some(_) => None,
+                    None => Some(Subset::Pll),
+                };
+                self.trigger_pressed = false;
+                self.timer = Timer::default();
+                self.inspection_started = None;
+                self.splits.clear();
+                self.stopwatch.stop();
+                let _ = self.config.set("trainer", self.trainer);
+                self.load_record();
+                self.next_drill();
+            }
+            Message::TrainerSubset(uid) => {
+                self.trainer = Some(trainer::SUBSETS[uid]);
+                let _ = self.config.set("trainer", self.trainer);
+                self.load_record();
+                self.drill = None;
+                self.next_drill();
+            }
+            Message::TrainerRecognise => {
+                if let Some(drill) = self.drill.as_mut() {
+                    drill.recognise();
+                }
+            }
+            Message::TrainerAnswer(known) => {
+                if let Some(solve) = self
+                    .drill
+                    .as_ref()
+                    .filter(|drill| drill.recognised.is_some())
+                    .map(|drill| drill.solve(known))
+                {
+                    self.edit_record(|record| record.add_solve(solve));
+                    self.next_drill();
+                }
+            }
+            Message::PrecisionUpdate(uid) => {
+                self.precision = Precision::ALL[uid];
+                let _ = self.config.set("precision", self.precision);
+            }
+            Message::ToggleWcaRounding(wca_rounding) => {
+                self.wca_rounding = wca_rounding;
+                let _ = self.config.set("wca_rounding", self.wca_rounding);
+            }
+            Message::ToggleInspection(inspection) => {
+                self.inspection = inspection;
+                let _ = self.config.set("inspection", self.inspection);
+            }
+            Message::InspectionTick => {
+                if let Some(started) = self.inspection_started
+                    && let Some(&mark) = timer::INSPECTION_CUES.get(self.inspection_cues)
+                    && started.elapsed() >= Duration::from_millis(mark.into())
+                {
+                    self.inspection_cues += 1;
+                    self.play(Cue::Inspection);
+                }
+            }
+            Message::PhasesUpdate(uid) => {
+                self.phases = uid + 1;
+                let _ = self.config.set("phases", self.phases);
+            }
+            Message::ShareTemplateUpdate(uid) => {
+                self.share_template = Template::ALL[uid];
+                let _ = self.config.set("share_template", self.share_template);
+            }
+            Message::SelectDay(date) => {
+                self.selected_day = match self.selected_day == Some(date) {
+                    true => None,
+                    false => Some(date),
+                };
+            }
+            Message::GoalKindUpdate(uid) => {
+                self.goal_kind = uid;
+                self.goal_error = None;
+            }
+            Message::GoalTargetInput(input) => {
+                self.goal_target_input = input;
+                self.goal_error = None;
+            }
+            Message::GoalDeadlineInput(input) => {
+                self.goal_deadline_input = input;
+                self.goal_error = None;
+            }
+            Message::AddGoal => {
+                let kind = Kind::ALL[self.goal_kind];
+                let Some(target) = self.parse_goal_target(kind) else {
+                    self.goal_error = Some(fl!("goal-target-invalid"));
+                    return Task::none();
+                };
+                let deadline = match self.goal_deadline_input.trim() {
+                    "" => None,
+                    input => match goal::parse_deadline(input) {
+                        Some(deadline) if deadline > unix_now() => Some(deadline),
+                        _ => {
+                            self.goal_error = Some(fl!("goal-deadline-invalid"));
+                            return Task::none();
+                        }
+                    },
+                };
+                self.goals.push(Goal {
+                    cube: self.current_cube.clone(),
+                    kind,
+                    target,
+                    created: unix_now(),
+                    deadline,
+                    achieved: None,
+                });
+                let _ = self.config.set("goals", &self.goals);
+                self.goal_target_input.clear();
+                self.goal_deadline_input.clear();
+            }
+            Message::RemoveGoal(index) => {
+                if index < self.goals.len() {
+                    self.goals.remove(index);
+                    let _ = self.config.set("goals", &self.goals);
+                }
+            }
+            Message::ToggleSounds(enabled) => {
+                self.sounds = enabled;
+                let _ = self.config.set("sounds", self.sounds);
+            }
+            Message::SoundThemeUpdate(uid) => {
+                self.sound_theme = sound::Theme::ALL[uid];
+                let _ = self.config.set("sound_theme", self.sound_theme);
+                // A sample of the chosen theme
+                self.play(Cue::Ready);
+            }
+            Message::VolumeUpdate(volume) => {
+                self.volume = volume;
+                let _ = self.config.set("volume", self.volume);
+            }
+            Message::ToggleSpeakResults(enabled) => {
+                self.speak_results = enabled;
+                let _ = self.config.set("speak_results", self.speak_results);
+            }
+            Message::ManualInput(input) => {
+                self.manual_input = input;
+                self.manual_error = false;
+            }
+            Message::ManualSubmit => match timer::parse_entry(&self.manual_input) {
+                Some(entry) => {
+                    self.add_entry(entry);
+                    self.manual_input.clear();
+                }
+                None => self.manual_error = true,
+            },
+            Message::Key(modifiers, key) => {
+                for (key_bind, action) in &self.key_binds {
+                    if key_bind.matches(modifiers, &key) {
+                        return self.update(menu::action::MenuAction::message(action));
+                    }
+                }
+            }
+            Message::Undo => {
+                if let Some(edit) = self.history.undo().cloned() {
+                    for change in edit.changes.iter().rev() {
+                        self.restore_record(&change.key, |record| change.undo(record));
+                    }
+                }
+            }
+            Message::Redo => {
+                if let Some(edit) = self.history.redo().cloned() {
+                    for change in &edit.changes {
+                        self.restore_record(&change.key, |record| change.redo(record));
+                    }
+                }
+            }
+            Message::ToastUndo(id) => {
+                self.toasts.remove(id);
+                return self.update(Message::Undo);
+            }
+            Message::CloseToast(id) => {
+                self.toasts.remove(id);
+            }
+            Message::SetPenalty(id, penalty) => {
+                self.edit_record(|record| {
+                    if let Some(solve) = record.solve_mut(id) {
+                        solve.set_penalty(penalty);
+                    }
+                });
+                return self.undo_toast(fl!("penalty-changed"));
+            }
+            Message::CommentInput(comment) => {
+                self.comment_input = comment;
+            }
+            Message::SaveComment(id, comment) => {
+                let comment = comment.trim().to_string();
+                self.edit_record(|record| {
+                    if let Some(solve) = record.solve_mut(id) {
+                        solve.set_comment((!comment.is_empty()).then_some(comment));
+                    }
+                });
+            }
+            Message::OpenSolve(id) => {
+                self.selected_solve = Some(id);
+                self.sync_comment();
+                self.context_page = ContextPage::Solve;
+                self.core.window.show_context = true;
+            }
+            Message::DetailCommentInput(comment) => {
+                self.detail_comment = comment;
+            }
+            Message::CopySolve(id) => {
+                if let Some(solve) = self.record.solve(id) {
+                    let mut text = format!(
+                        "{} {}",
+                        solve.result(Precision::Thousandths),
+                        solve.scramble.join(" ")
+                    );
+                    if let Some(comment) = &solve.comment {
+                        text = format!("{text} // {comment}");
+                    }
+                    return cosmic::iced::clipboard::write(text);
+                }
+            }
+            Message::CopyAverage(id, count) => {
+                if let Some(text) = self.record.position(id).and_then(|index| {
+                    share::average_text(
+                        &self.record,
+                        &self.record_cube(),
+                        index,
+                        count,
+                        self.share_template,
+                        self.precision,
+                    )
+                }) {
+                    return Task::batch([
+                        cosmic::iced::clipboard::write(text),
+                        self.toasts
+                            .push(Toast::new(fl!("average-copied", count = count)))
+                            .map(cosmic::Action::App),
+                    ]);
+                }
+            }
+            Message::RetrySolve(id) => {
+                if let Some(solve) = self.record.solve(id) {
+                    self.current_scramble = solve.scramble.clone();
+                    self.current_case = solve.case.clone();
+                    self.reset_virtual_cube();
+                    self.fmc_attempt = None;
+                    self.core.window.show_context = false;
+                }
+            }
+            Message::MoveSolve(id, cube) => {
+                let target = self.cube_options[cube].clone();
+                let key = target.config_key().to_string();
+                let Some(solve) = self.record.solve(id).cloned() else {
+                    return Task::none();
+                };
+                if key == self.record_key() {
+                    return Task::none();
+                }
+                let before = match self.config.get::<Record>(&key) {
+                    // Saving would drop whatever the newer version added
+                    Ok(record) if record.version > record::VERSION => {
+                        let message = fl!("solve-move-newer", puzzle = target.as_string());
+                        return self
+                            .toasts
+                            .push(Toast::new(message))
+                            .map(cosmic::Action::App);
+                    }
+                    Ok(record) => record.migrate(),
+                    Err(cosmic_config::Error::NotFound) => Record {
+                        cube: target.clone(),
+                        ..Record::default()
+                    },
+                    Err(err) => {
+                        self.report(Err(err));
+                        return Task::none();
+                    }
+                };
+                let message = fl!("solve-moved", puzzle = target.as_string());
+
+                // Solves are kept newest first, and only once if it was moved there before
+                let mut after = before.clone();
+                after.remove_solve(solve.id);
+                let position = after
+                    .solves
+                    .iter()
+                    .position(|s| s.timestamp < solve.timestamp)
+                    .unwrap_or(after.solves.len());
+                after.solves.insert(position, solve);
+                after.recalculate();
+                if let Err(err) = self.config.set(&key, &after) {
+                    self.report(Err(err));
+                    return Task::none();
+                }
+
+                let change = Change::between(key, &before, &after);
+                self.edit_records(vec![change], |record| record.remove_solve(id));
+                self.close_solve();
+                return self.undo_toast(message);
+            }
+            Message::BackupTick => {
+                if self.backup_due {
+                    self.backup(Reason::Scheduled);
+                }
+            }
+            Message::BackupNow => {
+                self.backup(Reason::Manual);
+            }
+            Message::DialogRestoreBackup(slot) => {
+                self.dialog_pages
+                    .push_front(DialogPage::RestoreBackup(slot));
+            }
+            Message::RestoreBackup(slot) => {
+                self.dialog_pages.pop_front();
+                match self.backups.load(slot) {
+                    Ok(snapshot) => {
+                        self.backup(Reason::BeforeRestore);
+                        // Records started after the snapshot was taken go back to being empty
+                        for (key, record) in self.all_records() {
+                            if !snapshot.records.iter().any(|(k, _)| *k == key) {
+                                let empty = Record {
+                                    cube: record.cube,
+                                    ..Record::default()
+                                };
+                                let result = self.config.set(&key, &empty);
+                                self.report(result);
+                            }
+                        }
+                        for (key, record) in snapshot.records {
+                            let result = self.config.set(&key, &record.migrate());
+                            self.report(result);
+                        }
+                        // Edits made before the restore no longer apply
+                        self.history = History::default();
+                        self.load_record();
+                    }
+                    Err(err) => self.report(Err(err)),
+                }
+            }
+            Message::DismissRecordError => {
+                self.record_error = None;
+            }
+            Message::ToggleOverlay(enabled) => {
+                self.overlay_enabled = enabled;
+                let _ = self.config.set("overlay", self.overlay_enabled);
+                // Dropping the sender shuts the server down
+                self.overlay = None;
+                if enabled {
+                    return self.start_overlay();
+                }
+            }
+            Message::OverlayPortInput(input) => {
+                self.overlay_port_input = input;
+            }
+            Message::OverlayPortSubmit => {
+                if let Ok(port) = self.overlay_port_input.trim().parse::<u16>()
+                    && port >= 1024
+                    && port != self.overlay_port
+                {
+                    self.overlay_port = port;
+                    let _ = self.config.set("overlay_port", self.overlay_port);
+                    if self.overlay.take().is_some() {
+                        return self.start_overlay();
+                    }
+                }
+            }
+            Message::OverlayStopped(generation, result) => {
+                // A server that was replaced reports its own failure, e.g. its port still in use
+                if generation != self.overlay_generation {
+                    return Task::none();
+                }
+                if let Err(err) = result {
+                    tracing::error!("Failed to serve overlay: {err}");
+                    self.overlay = None;
+                    return self
+                        .toasts
+                        .push(Toast::new(fl!("overlay-failed", error = err)))
+                        .map(cosmic::Action::App);
+                }
+            }
+            Message::SyncKindUpdate(kind) => {
+                self.sync_kind = kind;
+                self.sync_error = None;
+            }
+            Message::ChooseSyncFolder => {
+                return Task::perform(sync::choose_folder(fl!("sync-folder")), |result| {
+                    cosmic::Action::App(Message::SyncFolderChosen(result))
+                });
+            }
+            Message::SyncFolderChosen(Ok(folder)) => {
+                if folder.is_some() {
+                    self.sync_folder = folder;
+                    self.sync_error = None;
+                }
+            }
+            Message::SyncFolderChosen(Err(err)) => {
+                tracing::error!("Failed to choose a sync folder: {err}");
+                self.sync_error = Some(fl!("sync-folder-not-chosen", error = err));
+            }
+            Message::SyncUrlInput(input) => {
+                self.sync_url_input = input;
+            }
+            Message::SyncUsernameInput(input) => {
+                self.sync_username_input = input;
+            }
+            Message::SyncPasswordInput(input) => {
+                self.sync_password_input = input;
+            }
+            Message::SaveSyncTarget => {
+                let target = match self.sync_kind {
+                    1 => match &self.sync_folder {
+                        Some(folder) => Target::Folder(folder.clone()),
+                        None => {
+                            self.sync_error = Some(fl!("sync-folder-empty"));
+                            return Task::none();
+                        }
+                    },
+                    2 if !self.sync_url_input.trim().starts_with("http") => {
+                        self.sync_error = Some(fl!("sync-url-invalid"));
+                        return Task::none();
+                    }
+                    2 => Target::WebDav {
+                        url: self.sync_url_input.trim().to_string(),
+                        username: self.sync_username_input.clone(),
+                        password: self.sync_password_input.clone(),
+                    },
+                    _ => Target::Off,
+                };
+                // What was known of the old place says nothing about the new one
+                if target != self.sync_target {
+                    let _ = self
+                        .config
+                        .set("sync_state", HashMap::<String, sync::State>::new());
+                }
+                self.sync_target = target;
+                self.sync_error = None;
+                let _ = self.config.set("sync_target", &self.sync_target);
+                return Task::batch([self.store_sync_password(), self.start_sync()]);
+            }
+            Message::SyncPasswordLoaded(Ok(loaded)) => {
+                if let Target::WebDav { password, .. } = &mut self.sync_target {
+                    *password = loaded.clone();
+                    self.sync_password_input = loaded;
+                }
+                return self.start_sync();
+            }
+            Message::SyncPasswordLoaded(Err(err)) => {
+                tracing::error!("Failed to read the WebDAV password: {err}");
+                self.sync_error = Some(fl!("sync-password-not-read", error = err));
+            }
+            // Syncing goes on with the password typed in, but it has to be typed again next time
+            Message::SyncPasswordStored(Err(err)) => {
+                tracing::error!("Failed to save the WebDAV password: {err}");
+                return self
+                    .toasts
+                    .push(Toast::new(fl!("sync-password-not-saved", error = err)))
+                    .map(cosmic::Action::App);
+            }
+            Message::SyncPasswordStored(Ok(())) => {
+                // Settings from older versions still hold the password until saved again
+                let _ = self.config.set("sync_target", &self.sync_target);
+            }
+            Message::SyncNow => {
+                return self.start_sync();
+            }
+            Message::SyncFetched(Ok(fetched)) => {
+                let Some(backend) = self.sync_target.backend() else {
+                    self.syncing = false;
+                    return Task::none();
+                };
+                let shared = self.merge_synced(fetched);
+                return Task::perform(sync::publish(backend, shared), |result| {
+                    cosmic::Action::App(Message::SyncDone(result))
+                });
+            }
+            Message::SyncFetched(Err(err)) | Message::SyncDone(Err(err)) => {
+                tracing::error!("Failed to sync: {err}");
+                self.syncing = false;
+                self.sync_error = Some(err);
+            }
+            Message::SyncDone(Ok(())) => {
+                self.syncing = false;
+                self.sync_error = None;
+                self.last_sync = Some(unix_now());
+                let _ = self.config.set("last_sync", self.last_sync);
+            }
+            Message::Remote(dbus::Event::Connected(remote)) => {
+                self.remote = Some(remote);
+            }
+            Message::Remote(dbus::Event::Request(request)) => match request {
+                Request::Start => {
+                    if self.trigger_timer_enabled() && self.timer.status != Status::Running {
+                        self.start_timer();
+                    }
+                }
+                Request::Stop => {
+                    if self.trigger_timer_enabled() && self.timer.status == Status::Running {
+                        let elapsed = timer::ms_from_duration(self.stopwatch.elapsed());
+                        self.finish_solve(elapsed, None);
+                    }
+                }
+                Request::Cancel => self.cancel_timer(),
+                Request::SwitchPuzzle(cube) => {
+                    if let Some(uid) = self.cube_options.iter().position(|c| *c == cube) {
+                        return self.update(Message::CubeUpdate(uid));
+                    }
+                }
+                Request::AddSolve(entry) => {
+                    if self.entries_enabled() {
+                        self.add_entry(entry);
+                    }
+                }
+            },
+        }
+        self.sync_remote();
+        self.sync_overlay();
+        Task::batch([self.check_goals(), self.fill_scramble_queue()])
+    }
+
+    fn on_nav_select(&mut self, id: nav_bar::Id) -> Task<cosmic::Action<Self::Message>> {
+        self.nav.activate(id);
+        self.update_title()
+    }
+}
+
+impl AppModel {
+    pub fn update_title(&mut self) -> Task<cosmic::Action<Message>> {
+        let mut window_title = fl!("app-title");
+
+        if let Some(page) = self.nav.text(self.nav.active()) {
+            window_title.push_str(" — ");
+            window_title.push_str(page);
+        }
+
+        if let Some(id) = self.core.main_window_id() {
+            self.set_window_title(window_title, id)
+        } else {
+            Task::none()
+        }
+    }
+
+    fn timer_view(&self) -> Element<'_, Message> {
+        let active_theme = cosmic::theme::active();
+        let divider_color = match self.timer.status {
+            Status::Hold => active_theme.cosmic().destructive_color(),
+            Status::Ready => active_theme.cosmic().success_color(),
+            _ => active_theme.cosmic().accent_color(),
+        };
+        let displayed_time = match self.inspection_started {
+            Some(started) => timer::format_inspection(timer::ms_from_duration(started.elapsed())),
+            None if self.timer.status == Status::Running && self.hide_timer => fl!("solving"),
+            None => self.timer.display(self.precision),
+        };
+
+        let mut content = widget::column([])
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .push(
+                widget::text::text(displayed_time)
+                    .size(140)
+                    .width(Length::Fill)
+                    .align_x(Alignment::Center),
+            )
+            .push(
+                container("")
+                    .height(13)
+                    .width(150)
+                    .style(move |_| container::Style {
+                        background: Some(iced::Background::Color(cosmic::iced::Color::from_rgb(
+                            divider_color.red,
+                            divider_color.green,
+                            divider_color.blue,
+                        ))),
+                        border: Border {
+                            radius: 20.into(),
+                            width: 0.0,
+                            color: iced::Color::TRANSPARENT,
+                        },
+                        ..Default::default()
+                    }),
+            );
+
+        // Phases finished so far in a multi-phase solve
+        if !self.splits.is_empty() && !self.hide_timer {
+            content = content.push(
+                widget::text::body(
+                    self.splits
+                        .iter()
+                        .map(|&split| timer::format_from_ms(split, self.precision))
+                        .collect::<Vec<_>>()
+                        .join(" · "),
+                )
+                .size(20),
+            );
+        }
+
+        content.into()
+    }
+
+    fn start_timer(&mut self) {
+        self.started_at = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.as_millis() as u64);
+        self.timer.time = 0;
+        self.splits.clear();
+        self.penalty = self
+            .inspection_started
+            .take()
+            .map_or(Penalty::None, |started| {
+                timer::inspection_penalty(timer::ms_from_duration(started.elapsed()))
+            });
+        self.stopwatch.reset_and_start();
+        self.timer.status = Status::Running;
+    }
+
+    // Record the solve underway, ending at `elapsed`, and move on to the next scramble
+    fn finish_solve(&mut self, elapsed: u32, solution: Option<String>) {
+        self.timer.time = match self.wca_rounding {
+            true => timer::wca_round(elapsed),
+            false => elapsed,
+        };
+        let mut solve = Solve::new(self.timer.time, &self.current_scramble);
+        solve.case = self.current_case.clone();
+        solve.splits = std::mem::take(&mut self.splits);
+        solve.solution = solution;
+        solve.dnf = self.penalty == Penalty::Dnf;
+        solve.plus_two = self.penalty == Penalty::PlusTwo;
+        self.timer.status = Status::Stopped;
+        let best = self.record.best();
+        self.edit_record(|record| record.add_solve(solve));
+        self.solve_cues(best);
+        self.rescramble();
+    }
+
+    // Abandon the solve underway without recording it
+    fn cancel_timer(&mut self) {
+        self.trigger_pressed = false;
+        self.pads_held = [false; 2];
+        self.inspection_started = None;
+        self.timer = Timer::default();
+        self.splits.clear();
+        self.stopwatch.stop();
+        self.reset_virtual_cube();
+    }
+
+    // Record a typed result for the current scramble
+    fn add_entry(&mut self, entry: Entry) {
+        let mut solve = match entry {
+            Entry::Time(time) | Entry::PlusTwo(time) if self.wca_rounding => {
+                Solve::new(timer::wca_round(time), &self.current_scramble)
+            }
+            Entry::Time(time) | Entry::PlusTwo(time) => Solve::new(time, &self.current_scramble),
+            Entry::Dnf => Solve::new(0, &self.current_scramble),
+        };
+        solve.plus_two = matches!(entry, Entry::PlusTwo(_));
+        solve.case = self.current_case.clone();
+        solve.dnf = entry == Entry::Dnf;
+        let best = self.record.best();
+        self.edit_record(|record| record.add_solve(solve));
+        self.solve_cues(best);
+        self.rescramble();
+    }
+
+    fn play(&self, cue: Cue) {
+        if self.sounds {
+            sound::play(cue, self.sound_theme, f32::from(self.volume) / 100.0);
+        }
+    }
+
+    // Fanfare for a new best, and the result read aloud, for the solve just recorded
+    fn solve_cues(&self, previous_best: Option<u32>) {
+        let Some(solve) = self.record.solves.first() else {
+            return;
+        };
+        if !solve.dnf && previous_best.is_some_and(|best| solve.value() < best) {
+            self.play(Cue::PersonalBest);
+        }
+        if self.speak_results && self.can_speak {
+            sound::speak(&solve.result(self.precision));
+        }
+    }
+
+    // Typed results are recorded as times, which FMC and the trainer don't use
+    fn entries_enabled(&self) -> bool {
+        self.current_cube != Cube::ThreeFmc && self.trainer.is_none()
+    }
+
+    // Tell programs using the D-Bus service where the timer is up to
+    fn sync_remote(&self) {
+        let Some(remote) = &self.remote else {
+            return;
+        };
+        remote.update(dbus::State {
+            status: self.timer.status.as_str().to_string(),
+            scramble: self.current_scramble.join(" "),
+            puzzle: self.current_cube.as_string(),
+            timer_enabled: self.trigger_timer_enabled(),
+            entries_enabled: self.entries_enabled(),
+        });
+    }
+
+    fn start_overlay(&mut self) -> Task<cosmic::Action<Message>> {
+        let (sender, receiver) = watch::channel(self.overlay_state());
+        self.overlay = Some(sender);
+        self.overlay_generation += 1;
+        let generation = self.overlay_generation;
+        Task::perform(overlay::serve(self.overlay_port, receiver), move |result| {
+            cosmic::Action::App(Message::OverlayStopped(generation, result))
+        })
+    }
+
+    // The WebDAV password is read from the keyring before the first sync, unless it came from
+    // settings saved by an older version, which move it there
+    fn load_sync_password(&mut self) -> Task<cosmic::Action<Message>> {
+        match &self.sync_target {
+            Target::WebDav { password, .. } if !password.is_empty() => {
+                Task::batch([self.store_sync_password(), self.start_sync()])
+            }
+            Target::WebDav { url, username, .. } => Task::perform(
+                sync::load_password(url.clone(), username.clone()),
+                |result| cosmic::Action::App(Message::SyncPasswordLoaded(result)),
+            ),
+            _ => self.start_sync(),
+        }
+    }
+
+    fn store_sync_password(&self) -> Task<cosmic::Action<Message>> {
+        let Target::WebDav {
+            url,
+            username,
+            password,
+        } = &self.sync_target
+        else {
+            return Task::none();
+        };
+        Task::perform(
+            sync::store_password(url.clone(), username.clone(), password.clone()),
+            |result| cosmic::Action::App(Message::SyncPasswordStored(result)),
+        )
+    }
+
+    // Load what's shared, to be merged when it arrives
+    fn start_sync(&mut self) -> Task<cosmic::Action<Message>> {
+        let Some(backend) = self.sync_target.backend() else {
+            return Task::none();
+        };
+        if self.syncing {
+            return Task::none();
+        }
+        self.syncing = true;
+        let keys = Cube::ALL
+            .iter()
+            .map(|cube| cube.config_key().to_string())
+            .collect();
+        Task::perform(sync::fetch(backend, keys), |result| {
+            cosmic::Action::App(Message::SyncFetched(result))
+        })
+    }
+
+    // Merge fetched records into the stored ones, returning what to share back. This happens
+    // here rather than alongside the fetch so solves added in the meantime aren't lost.
+    fn merge_synced(
+        &mut self,
+        fetched: Vec<(String, sync::Shared)>,
+    ) -> Vec<(String, sync::Shared)> {
+        let mut states = self
+            .config
+            .get::<HashMap<String, sync::State>>("sync_state")
+            .unwrap_or_default();
+        let now = unix_now();
+        let mut changed_current = false;
+        let mut changed_any = false;
+        let mut publish = vec![];
+        for (key, shared) in fetched {
+            let Some(&cube) = Cube::ALL.iter().find(|cube| cube.config_key() == key) else {
+                continue;
+            };
+            let current = key == self.record_key();
+            let mut record = if current {
+                if self.record_read_only {
+                    continue;
+                }
+                self.record.clone()
+            } else {
+                match self.config.get::<Record>(&key) {
+                    // Saving would drop whatever the newer version added
+                    Ok(record) if record.version > record::VERSION => continue,
+                    Ok(record) => record.migrate(),
+                    Err(cosmic_config::Error::NotFound) => Record {
+                        cube,
+                        ..Record::default()
+                    },
+                    Err(err) => {
+                        tracing::error!("Failed to load record to sync: {err}");
+                        continue;
+                    }
+                }
+            };
+            let state = states.entry(key.clone()).or_default();
+            let (merged, changed) = sync::merge(&mut record, shared.clone(), state, now);
+            if changed {
+                let result = self.config.set(&key, &record);
+                self.report(result);
+                if current {
+                    self.record = record;
+                    changed_current = true;
+                }
+                changed_any = true;
+            }
+            if merged != shared {
+                publish.push((key, merged));
+            }
+        }
+        let _ = self.config.set("sync_state", states);
+
+        if changed_any {
+            // Undoing an edit from before the sync would bring back what it changed
+            self.history = History::default();
+            self.backup_due = true;
+        }
+        if changed_current {
+            // The detail page stays open unless its solve was deleted elsewhere
+            if self
+                .selected_solve
+                .is_some_and(|id| self.record.solve(id).is_none())
+            {
+                self.close_solve();
+            }
+            self.sync_comment();
+        }
+        publish
+    }
+
+    fn overlay_state(&self) -> overlay::State {
+        let cube = self.record_cube();
+        let running = self.timer.status == Status::Running;
+        overlay::State {
+            puzzle: cube.as_string(),
+            scramble: self.current_scramble.join(" "),
+            status: self.timer.status.as_str().to_string(),
+            // A running time is counted on the page, rather than sent on every tick
+            time: match self.inspection_started {
+                _ if running => String::new(),
+                Some(started) => {
+                    timer::format_inspection(timer::ms_from_duration(started.elapsed()))
+                }
+                None => self.timer.display(self.precision),
+            },
+            started: running.then_some(self.started_at),
+            digits: self.precision.digits(),
+            last_solve: self
+                .record
+                .solves
+                .first()
+                .map(|solve| solve.result(self.precision)),
+            ao5: self
+                .record
+                .ao5
+                .map(|value| cube.format_result(value, self.precision)),
+            ao12: self
+                .record
+                .ao12
+                .map(|value| cube.format_result(value, self.precision)),
+        }
+    }
+
+    fn sync_overlay(&self) {
+        let Some(sender) = &self.overlay else {
+            return;
+        };
+        let state = self.overlay_state();
+        sender.send_if_modified(|current| {
+            let modified = *current != state;
+            *current = state;
+            modified
+        });
+    }
+
+    fn trigger_label(&self) -> String {
+        match &self.trigger {
+            Input::Key(key) if key == " " => fl!("trigger-space"),
+            Input::Key(key) => key.to_uppercase(),
+            Input::Mouse(button) => fl!("trigger-mouse", button = button.clone()),
+            Input::Gamepad(button) => fl!("trigger-gamepad", button = button.clone()),
+        }
+    }
+
+    fn trigger_timer_enabled(&self) -> bool {
+        self.current_cube != Cube::ThreeFmc
+            && !self.manual_entry
+            && !self.virtual_cube
+            && self.trainer.is_none()
+    }
+
+    // The virtual cube replaces the space bar timer, starting and stopping with the cube itself
+    fn virtual_cube_enabled(&self) -> bool {
+        self.virtual_cube
+            && self.current_cube != Cube::ThreeFmc
+            && !self.manual_entry
+            && self.trainer.is_none()
+    }
+
+    fn penalty_buttons(&self, id: Uuid) -> widget::Row<'_, Message> {
+        let mut content = widget::row([])
+            .spacing(cosmic::theme::active().cosmic().space_s())
+            .align_y(Alignment::Center);
+
+        let current = self.record.solve(id).map(|solve| solve.penalty());
+        for (penalty, label) in [
+            (Penalty::None, fl!("penalty-none")),
+            (Penalty::PlusTwo, String::from("+2")),
+            (Penalty::Dnf, String::from("DNF")),
+        ] {
+            let button = match current == Some(penalty) {
+                true => widget::button::suggested(label),
+                false => widget::button::standard(label),
+            };
+            content = content.push(button.on_press(Message::SetPenalty(id, penalty)));
+        }
+        content
+    }
+
+    fn last_solve_view(&self, id: Uuid) -> Element<'_, Message> {
+        self.penalty_buttons(id)
+            .push(
+                widget::text_input(fl!("comment-placeholder"), &self.comment_input)
+                    .on_input(Message::CommentInput)
+                    .width(Length::Fill),
+            )
+            .push(
+                widget::button::standard(fl!("comment-save"))
+                    .on_press(Message::SaveComment(id, self.comment_input.clone())),
+            )
+            .into()
+    }
+
+    fn solve_page(&self) -> Element<'_, Message> {
+        let active_theme = cosmic::theme::active();
+        let Some((index, solve)) = self
+            .selected_solve
+            .and_then(|id| self.record.position(id))
+            .map(|index| (index, &self.record.solves[index]))
+        else {
+            return widget::text::body(fl!("solve-missing")).into();
+        };
+        let cube = self.record_cube();
+
+        let mut content = widget::column([])
+            .spacing(active_theme.cosmic().space_m())
+            .push(widget::text::title2(solve.result(Precision::Thousandths)));
+        if solve.moves.is_none() {
+            content = content.push(self.penalty_buttons(solve.id));
+        }
+
+        // Raw time before penalties, or time taken for a fewest moves attempt
+        let mut details = settings::section().add(settings::item(
+            fl!("solve-time"),
+            widget::text::body(solve.time(match solve.moves {
+                Some(_) => Precision::Seconds,
+                None => Precision::Thousandths,
+            })),
+        ));
+        if let Some(timestamp) = solve.timestamp {
+            details = details.add(settings::item(
+                fl!("solve-date"),
+                widget::text::body(record::local_time(timestamp)),
+            ));
+        }
+        if let Some(case) = &solve.case {
+            details = details.add(settings::item(
+                fl!("solve-case"),
+                widget::text::body(case.clone()),
+            ));
+        }
+        if let Some(solution) = &solve.solution {
+            details = details.add(settings::item(
+                fl!("solve-solution"),
+                widget::text::body(solution.clone()),
+            ));
+        }
+        content = content.push(details);
+
+        // Scramble
+        content = content.push(widget::text::body(solve.scramble.join(" ")));
+        if let Some(state) = CubeState::scrambled(cube.size(), &solve.scramble) {
+            content = content.push(image::net(&state, 64));
+        }
+
+        // Comment
+        content = content.push(
+            widget::row([])
+                .spacing(active_theme.cosmic().space_s())
+                .align_y(Alignment::Center)
+                .push(
+                    widget::text_input(fl!("comment-placeholder"), &self.detail_comment)
+                        .on_input(Message::DetailCommentInput)
+                        .width(Length::Fill),
+                )
+                .push(
+                    widget::button::standard(fl!("comment-save"))
+                        .on_press(Message::SaveComment(solve.id, self.detail_comment.clone())),
+                ),
+        );
+
+        // Splits, the last phase ending with the solve itself
+        if !solve.splits.is_empty() {
+            let mut splits = settings::section().title(fl!("splits"));
+            let mut previous = 0;
+            for (i, &split) in solve.splits.iter().chain([&solve.time]).enumerate() {
+                splits = splits.add(
+                    settings::item::builder(fl!("phase", number = i + 1))
+                        .description(timer::format_from_ms(split, Precision::Thousandths))
+                        .control(widget::text::body(timer::format_from_ms(
+                            split.saturating_sub(previous),
+                            Precision::Thousandths,
+                        ))),
+                );
+                previous = split;
+            }
+            content = content.push(splits);
+        }
+
+        // Averages of this solve and the ones before it, and current averages it counts in
+        let mut averages = settings::section().title(fl!("averages"));
+        let mut current = vec![];
+        for count in [5, 12, 100] {
+            if let Some(value) = self.record.average_at(index, count) {
+                averages = averages.add(settings::item(
+                    fl!("average-ending-here", count = count),
+                    widget::row([])
+                        .spacing(active_theme.cosmic().space_s())
+                        .align_y(Alignment::Center)
+                        .push(widget::text::body(
+                            cube.format_result(value, self.precision),
+                        ))
+                        .push(
+                            widget::button::standard(fl!("copy-average"))
+                                .on_press(Message::CopyAverage(solve.id, count)),
+                        ),
+                ));
+            }
+            if index < count && self.record.solves.len() >= count {
+                current.push(format!("AO{count}"));
+            }
+        }
+        if !current.is_empty() {
+            averages = averages.add(widget::text::caption(fl!(
+                "in-current-averages",
+                averages = current.join(", ")
+            )));
+        }
+        content = content.push(averages);
+
+        // Actions
+        content = content.push(
+            widget::row([])
+                .spacing(active_theme.cosmic().space_s())
+                .push(widget::button::standard(fl!("copy")).on_press(Message::CopySolve(solve.id)))
+                .push(
+                    widget::button::standard(fl!("retry")).on_press_maybe(
+                        self.trainer
+                            .is_none()
+                            .then_some(Message::RetrySolve(solve.id)),
+                    ),
+                )
+                .push(
+                    widget::button::destructive(fl!("remove"))
+                        .on_press(Message::DialogRemoveSolve(solve.id)),
+                ),
+        );
+        if self.trainer.is_none() {
+            let id = solve.id;
+            content = content.push(settings::section().add(settings::item(
+                fl!("move-to-puzzle"),
+                dropdown(&self.cube_options_labels, None, move |cube| {
+                    Message::MoveSolve(id, cube)
+                }),
+            )));
+        }
+
+        content.into()
+    }
+
+    fn manual_entry_view(&self, padding: u16) -> Element<'_, Message> {
+        let active_theme = cosmic::theme::active();
+        let mut content = widget::column([])
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .push(Space::new().height(padding))
+            .push(widget::divider::horizontal::default())
+            .push(Space::new().height(padding))
+            .push(
+                widget::row([])
+                    .spacing(active_theme.cosmic().space_s())
+                    .align_y(Alignment::Center)
+                    .push(
+                        widget::text_input(fl!("manual-entry-placeholder"), &self.manual_input)
+                            .on_input(Message::ManualInput)
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        widget::button::suggested(fl!("manual-entry-add"))
+                            .on_press(Message::ManualSubmit),
+                    ),
+            );
+
+        if self.manual_error {
+            let error_color = active_theme.cosmic().destructive_color();
+            content = content.push(widget::text::body(fl!("manual-entry-invalid")).class(
+                cosmic::style::Text::Color(cosmic::iced::Color::from_rgb(
+                    error_color.red,
+                    error_color.green,
+                    error_color.blue,
+                )),
+            ));
+        }
+
+        content
+            .push(Space::new().height(padding))
+            .push(
+                widget::text::text(fl!("manual-entry-hint"))
+                    .size(16)
+                    .width(Length::Fill)
+                    .align_x(Alignment::Center),
+            )
+            .into()
+    }
+
+    fn touch_pads_view(&self) -> Element<'_, Message> {
+        let active_theme = cosmic::theme::active();
+        let held_color = match self.timer.status {
+            Status::Hold => active_theme.cosmic().destructive_color(),
+            Status::Ready => active_theme.cosmic().success_color(),
+            _ => active_theme.cosmic().accent_color(),
+        };
+        let idle_color = active_theme.cosmic().bg_component_color();
+        let pad =
+            |pad: usize, label: String| {
+                let color = match self.pads_held[pad] {
+                    true => held_color,
+                    false => idle_color,
+                };
+                widget::mouse_area(
+                    container(widget::text::title4(label))
+                        .center_x(Length::Fill)
+                        .center_y(160)
+                        .style(move |_| container::Style {
+                            background: Some(iced::Background::Color(
+                                cosmic::iced::Color::from_rgb(color.red, color.green, color.blue),
+                            )),
+                            border: Border {
+                                radius: 20.into(),
+                                width: 0.0,
+                                color: iced::Color::TRANSPARENT,
+                            },
+                            ..Default::default()
+                        }),
+                )
+                .on_press(Message::PadPressed(pad))
+            };
+
+        widget::row([])
+            .spacing(active_theme.cosmic().space_l())
+            .push(pad(0, fl!("touch-pad-left")))
+            .push(pad(1, fl!("touch-pad-right")))
+            .into()
+    }
+
+    fn virtual_cube_view(&self, padding: u16) -> Element<'_, Message> {
+        let mut content = widget::column([])
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .push(Space::new().height(padding))
+            .push(widget::divider::horizontal::default())
+            .push(self.timer_view())
+            .push(Space::new().height(padding));
+
+        let hint = match &self.virtual_state {
+            Some(cube) => {
+                content = content.push(image::net(&cube.state, 120));
+                match self.timer.status {
+                    Status::Running => fl!("virtual-cube-solving"),
+                    _ => fl!("virtual-cube-hint"),
+                }
+            }
+            None => fl!("virtual-cube-unsupported"),
+        };
+
+        content
+            .push(Space::new().height(padding))
+            .push(
+                widget::text::text(hint)
+                    .size(16)
+                    .width(Length::Fill)
+                    .align_x(Alignment::Center),
+            )
+            .into()
+    }
+
+    fn fmc_view(&self, padding: u16) -> Element<'_, Message> {
+        let active_theme = cosmic::theme::active();
+        let mut content = widget::column([])
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .push(Space::new().height(padding))
+            .push(widget::divider::horizontal::default());
+
+        let Some(attempt) = &self.fmc_attempt else {
+            return content
+                .push(Space::new().height(padding))
+                .push(widget::button::suggested(fl!("fmc-start")).on_press(Message::FmcStart))
+                .push(Space::new().height(padding))
+                .push(
+                    widget::text::text(fl!("fmc-hint", limit = fmc::MOVE_LIMIT))
+                        .size(16)
+                        .width(Length::Fill)
+                        .align_x(Alignment::Center),
+                )
+                .into();
+        };
+
+        // Countdown
+        let warning = attempt.warning();
+        let indicator_color = match warning {
+            Warning::OneMinute => active_theme.cosmic().destructive_color(),
+            Warning::FiveMinutes => active_theme.cosmic().warning_color(),
+            Warning::None => active_theme.cosmic().accent_color(),
+        };
+        content = content
+            .push(
+                widget::text::text(attempt.display())
+                    .size(140)
+                    .width(Length::Fill)
+                    .align_x(Alignment::Center),
+            )
+            .push(
+                container("")
+                    .height(13)
+                    .width(150)
+                    .style(move |_| container::Style {
+                        background: Some(iced::Background::Color(cosmic::iced::Color::from_rgb(
+                            indicator_color.red,
+                            indicator_color.green,
+                            indicator_color.blue,
+                        ))),
+                        border: Border {
+                            radius: 20.into(),
+                            width: 0.0,
+                            color: iced::Color::TRANSPARENT,
+                        },
+                        ..Default::default()
+                    }),
+            );
+        match warning {
+            Warning::OneMinute => {
+                content = content.push(widget::text::title4(fl!("fmc-one-minute")));
+            }
+            Warning::FiveMinutes => {
+                content = content.push(widget::text::title4(fl!("fmc-five-minutes")));
+            }
+            Warning::None => (),
+        }
+
+        // Solution entry
+        content = content.push(Space::new().height(padding)).push(
+            widget::text_input(fl!("fmc-solution-placeholder"), &attempt.solution)
+                .on_input(Message::FmcSolutionInput)
+                .width(Length::Fill),
+        );
+        if let Some(error) = &attempt.error {
+            let error_text = match error {
+                SolutionError::Empty => fl!("fmc-empty"),
+                SolutionError::InvalidMove(token) => fl!("fmc-invalid-move", token = token.clone()),
+                SolutionError::TooLong(count) => {
+                    fl!("fmc-too-long", count = *count, limit = fmc::MOVE_LIMIT)
+                }
+                SolutionError::NotSolved => fl!("fmc-not-solved"),
+            };
+            let error_color = active_theme.cosmic().destructive_color();
+            content = content.push(widget::text::body(error_text).class(
+                cosmic::style::Text::Color(cosmic::iced::Color::from_rgb(
+                    error_color.red,
+                    error_color.green,
+                    error_color.blue,
+                )),
+            ));
+        }
+
+        content
+            .push(Space::new().height(padding))
+            .push(
+                widget::row([])
+                    .spacing(active_theme.cosmic().space_s())
+                    .push(widget::button::standard(fl!("fmc-give-up")).on_press(Message::FmcCancel))
+                    .push(
+                        widget::button::suggested(fl!("fmc-submit")).on_press(Message::FmcSubmit),
+                    ),
+            )
+            .into()
+    }
+
+    fn trainer_view(&self, padding: u16) -> Element<'_, Message> {
+        let active_theme = cosmic::theme::active();
+        let selected_subset = trainer::SUBSETS
+            .iter()
+            .position(|s| Some(*s) == self.trainer);
+
+        let content = widget::column([])
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .push(dropdown(
+                &self.trainer_options_labels,
+                selected_subset,
+                Message::TrainerSubset,
+            ))
+            .push(Space::new().height(padding))
+            .push(widget::divider::horizontal::default())
+            .push(Space::new().height(padding));
+
+        let Some(drill) = &self.drill else {
+            return content.into();
+        };
+        let content = content
+            .push(image::last_layer(&drill.state, 48))
+            .push(Space::new().height(padding));
+
+        match drill.recognised {
+            None => content
+                .push(
+                    widget::button::suggested(fl!("trainer-recognised"))
+                        .on_press(Message::TrainerRecognise),
+                )
+                .push(Space::new().height(padding))
+                .push(
+                    widget::text::text(fl!("trainer-hint", trigger = self.trigger_label()))
+                        .size(16)
+                        .width(Length::Fill)
+                        .align_x(Alignment::Center),
+                ),
+            Some(time) => content
+                .push(widget::text::text(timer::format_from_ms(time, self.precision)).size(60))
+                .push(widget::text::title3(drill.case.name))
+                .push(widget::text::body(drill.case.alg))
+                .push(Space::new().height(padding))
+                .push(
+                    widget::row([])
+                        .spacing(active_theme.cosmic().space_s())
+                        .push(
+                            widget::button::standard(fl!("trainer-missed"))
+                                .on_press(Message::TrainerAnswer(false)),
+                        )
+                        .push(
+                            widget::button::suggested(fl!("trainer-known"))
+                                .on_press(Message::TrainerAnswer(true)),
+                        ),
+                ),
+        }
+        .into()
+    }
+
+    fn backups_page(&self) -> Element<'_, Message> {
+        let mut snapshots = settings::section().title(fl!("snapshots"));
+        for summary in &self.backups.snapshots {
+            let reason = match summary.reason {
+                Reason::Scheduled => fl!("backup-scheduled"),
+                Reason::Manual => fl!("backup-manual"),
+                Reason::BeforeRemove => fl!("backup-before-remove"),
+                Reason::BeforeClear => fl!("backup-before-clear"),
+                Reason::BeforeRestore => fl!("backup-before-restore"),
+                Reason::BeforeUpgrade => fl!("backup-before-upgrade"),
+            };
+            snapshots = snapshots.add(
+                settings::item::builder(record::local_time(summary.timestamp))
+                    .description(format!(
+                        "{reason} · {}",
+                        fl!("case-solves", count = summary.solves)
+                    ))
+                    .control(
+                        widget::button::standard(fl!("restore"))
+                            .on_press(Message::DialogRestoreBackup(summary.slot)),
+                    ),
+            );
+        }
+
+        widget::column([])
+            .spacing(cosmic::theme::active().cosmic().space_m())
+            .push(widget::text::body(fl!("backups-description")))
+            .push(widget::button::standard(fl!("backup-now")).on_press(Message::BackupNow))
+            .push(snapshots)
+            .into()
+    }
+
+    fn scrambles_page(&self) -> Element<'_, Message> {
+        let active_theme = cosmic::theme::active();
+        let mut content = widget::column([])
+            .spacing(active_theme.cosmic().space_m())
+            .push(widget::text::body(fl!(
+                "custom-scrambles-description",
+                puzzle = self.current_cube.as_string()
+            )))
+            .push(
+                text_editor(&self.scramble_input)
+                    .placeholder(fl!("custom-scrambles-placeholder"))
+                    .on_action(Message::ScrambleEdit)
+                    .height(200),
+            );
+
+        if let Some(error) = &self.scramble_error {
+            let error_color = active_theme.cosmic().destructive_color();
+            content = content.push(widget::text::body(error.clone()).class(
+                cosmic::style::Text::Color(cosmic::iced::Color::from_rgb(
+                    error_color.red,
+                    error_color.green,
+                    error_color.blue,
+                )),
+            ));
+        }
+
+        content
+            .push(
+                widget::button::suggested(fl!("custom-scrambles-use"))
+                    .on_press(Message::UseCustomScrambles),
+            )
+            .into()
+    }
+
+    fn calendar_page(&self) -> Element<'_, Message> {
+        // Weeks of solves shown, ending with the current one
+        const WEEKS: i64 = 26;
+
+        let active_theme = cosmic::theme::active();
+        let cube = self.record_cube();
+        let tz = jiff::tz::TimeZone::system();
+        let today = jiff::Zoned::now().with_time_zone(tz.clone()).date();
+        let days = calendar::days(&self.record.solves, &tz);
+        let (current, longest) = calendar::streaks(&days, today);
+        let by_date: HashMap<_, _> = days.iter().map(|day| (day.date, day)).collect();
+        let most = days.iter().map(|day| day.solves.len()).max().unwrap_or(1);
+
+        // One column per week from Monday, darker the more solves there were on a day
+        let accent = active_theme.cosmic().accent_color();
+        let empty = active_theme.cosmic().bg_component_color();
+        let monday = today
+            .checked_sub(
+                jiff::Span::new()
+                    .days(i64::from(today.weekday().to_monday_zero_offset()) + (WEEKS - 1) * 7),
+            )
+            .unwrap_or(today);
+        let mut heatmap = widget::row([]).spacing(3);
+        for week in 0..WEEKS {
+            let mut column = widget::column([]).spacing(3);
+            for weekday in 0..7 {
+                let Ok(date) = monday.checked_add(jiff::Span::new().days(week * 7 + weekday))
+                else {
+                    continue;
+                };
+                if date > today {
+                    break;
+                }
+                let solves = by_date.get(&date).map_or(0, |day| day.solves.len());
+                let (color, alpha) = match solves {
+                    0 => (empty, 1.0),
+                    n => (accent, 0.3 + 0.7 * n as f32 / most as f32),
+                };
+                let selected = self.selected_day == Some(date);
+                column = column.push(
+                    widget::mouse_area(container("").width(14).height(14).style(move |_| {
+                        container::Style {
+                            background: Some(iced::Background::Color(
+                                cosmic::iced::Color::from_rgba(
+                                    color.red,
+                                    color.green,
+                                    color.blue,
+                                    alpha,
+                                ),
+                            )),
+                            border: Border {
+                                radius: 3.into(),
+                                width: if selected { 2.0 } else { 0.0 },
+                                color: cosmic::iced::Color::from_rgb(
+                                    accent.red,
+                                    accent.green,
+                                    accent.blue,
+                                ),
+                            },
+                            ..Default::default()
+                        }
+                    }))
+                    .on_press(Message::SelectDay(date)),
+                );
+            }
+            heatmap = heatmap.push(column);
+        }
+
+        let mut content = widget::column([])
+            .spacing(active_theme.cosmic().space_m())
+            .push(widget::text::body(fl!(
+                "calendar-description",
+                puzzle = cube.as_string()
+            )))
+            .push(
+                settings::section()
+                    .add(settings::item(
+                        fl!("streak-current"),
+                        widget::text::body(fl!("streak-days", count = current)),
+                    ))
+                    .add(settings::item(
+                        fl!("streak-longest"),
+                        widget::text::body(fl!("streak-days", count = longest)),
+                    ))
+                    .add(settings::item(
+                        fl!("days-practised"),
+                        widget::text::body(days.len().to_string()),
+                    )),
+            )
+            .push(scrollable::horizontal(heatmap));
+
+        // Summary of the chosen day, or today
+        let date = self.selected_day.unwrap_or(today);
+        let mut summary = settings::section().title(date.strftime("%A %-d %B %Y").to_string());
+        match by_date.get(&date) {
+            Some(day) => {
+                let result = |value: Option<u32>| match value {
+                    Some(value) => cube.format_result(value, self.precision),
+                    None => String::from("N/A"),
+                };
+                summary = summary
+                    .add(settings::item(
+                        fl!("day-solves"),
+                        widget::text::body(day.solves.len().to_string()),
+                    ))
+                    .add(settings::item(
+                        fl!("day-mean"),
+                        widget::text::body(result(day.mean)),
+                    ))
+                    .add(settings::item(
+                        fl!("day-best"),
+                        widget::text::body(result(day.best)),
+                    ));
+                for &i in &day.solves {
+                    let solve = &self.record.solves[i];
+                    summary = summary.add(settings::item(
+                        solve.timestamp.map(record::local_time).unwrap_or_default(),
+                        widget::text::body(solve.result(self.precision)),
+                    ));
+                }
+            }
+            None => {
+                summary = summary.add(widget::text::body(fl!("day-no-solves")));
+            }
+        }
+
+        content = content.push(summary);
+        content.into()
+    }
+
+    fn performance_page(&self) -> Element<'_, Message> {
+        // Fewer solves than this in an hour, weekday or position say little about it
+        const MIN_SOLVES: usize = 5;
+
+        let tz = jiff::tz::TimeZone::system();
+        let solves = &self.record.solves;
+        let hours = analysis::by_hour(solves, &tz);
+        let hour_labels: Vec<String> = (0..24).map(|hour| format!("{hour:02}:00")).collect();
+        let weekdays = analysis::by_weekday(solves, &tz);
+        let weekday_labels = vec![
+            fl!("weekday-monday"),
+            fl!("weekday-tuesday"),
+            fl!("weekday-wednesday"),
+            fl!("weekday-thursday"),
+            fl!("weekday-friday"),
+            fl!("weekday-saturday"),
+            fl!("weekday-sunday"),
+        ];
+        let positions = analysis::by_position(solves);
+        let position_labels: Vec<String> = (1..=positions.len())
+            .map(|position| match position {
+                analysis::MAX_POSITION => format!("{position}+"),
+                _ => position.to_string(),
+            })
+            .collect();
+
+        let chart = |title: String, buckets: &[Bucket], labels: &[String], every: usize| {
+            let summary = match analysis::extremes(buckets, MIN_SOLVES) {
+                Some((fastest, slowest)) if fastest != slowest => fl!(
+                    "performance-extremes",
+                    fastest = labels[fastest].clone(),
+                    fastest_mean = self.bucket_mean(&buckets[fastest]),
+                    slowest = labels[slowest].clone(),
+                    slowest_mean = self.bucket_mean(&buckets[slowest])
+                ),
+                _ => fl!("performance-too-few", count = MIN_SOLVES),
+            };
+            widget::column([])
+                .spacing(cosmic::theme::active().cosmic().space_xs())
+                .push(widget::text::heading(title))
+                .push(self.bar_chart(buckets, labels, every))
+                .push(widget::text::caption(summary))
+        };
+
+        widget::column([])
+            .spacing(cosmic::theme::active().cosmic().space_l())
+            .push(widget::text::body(fl!(
+                "performance-description",
+                puzzle = self.record_cube().as_string(),
+                minutes = analysis::BLOCK_GAP / 60
+            )))
+            .push(chart(fl!("performance-hour"), &hours, &hour_labels, 6))
+            .push(chart(
+                fl!("performance-weekday"),
+                &weekdays,
+                &weekday_labels,
+                1,
+            ))
+            .push(chart(
+                fl!("performance-position"),
+                &positions,
+                &position_labels,
+                5,
+            ))
+            .into()
+    }
+
+    fn bucket_mean(&self, bucket: &Bucket) -> String {
+        bucket
+            .mean
+            .map(|mean| self.record_cube().format_result(mean, self.precision))
+            .unwrap_or_else(|| String::from("N/A"))
+    }
+
+    // Bars as tall as the mean result in each bucket, labelled `every` so many bars
+    fn bar_chart(
+        &self,
+        buckets: &[Bucket],
+        labels: &[String],
+        every: usize,
+    ) -> Element<'_, Message> {
+        const HEIGHT: f32 = 100.0;
+
+        let accent = cosmic::theme::active().cosmic().accent_color();
+        let slowest = buckets
+            .iter()
+            .filter_map(|bucket| bucket.mean)
+            .max()
+            .unwrap_or(1)
+            .max(1);
+        let mut bars = widget::row([]).spacing(2).align_y(Alignment::End);
+        for (i, bucket) in buckets.iter().enumerate() {
+            let height = bucket
+                .mean
+                .map_or(0.0, |mean| (HEIGHT * mean as f32 / slowest as f32).max(2.0));
+            let label = match i % every {
+                0 => labels[i].clone(),
+                _ => String::new(),
+            };
+            bars = bars.push(
+                widget::column([])
+                    .width(Length::Fill)
+                    .align_x(Alignment::Center)
+                    .push(widget::tooltip(
+                        container("")
+                            .width(Length::Fill)
+                            .height(height)
+                            .style(move |_| container::Style {
+                                background: Some(iced::Background::Color(
+                                    cosmic::iced::Color::from_rgb(
+                                        accent.red,
+                                        accent.green,
+                                        accent.blue,
+                                    ),
+                                )),
+                                border: Border {
+                                    radius: 2.into(),
+                                    width: 0.0,
+                                    color: iced::Color::TRANSPARENT,
+                                },
+                                ..Default::default()
+                            }),
+                        widget::text::body(fl!(
+                            "performance-bar",
+                            label = labels[i].clone(),
+                            mean = self.bucket_mean(bucket),
+                            count = bucket.solves
+                        )),
+                        widget::tooltip::Position::Top,
+                    ))
+                    .push(widget::text::caption(label).size(10)),
+            );
+        }
+        bars.height(HEIGHT + 20.0).into()
+    }
+
+    fn goals_page(&self) -> Element<'_, Message> {
+        let active_theme = cosmic::theme::active();
+        let now = unix_now();
+        let mut goals =
+            settings::section().title(fl!("goals-for", puzzle = self.current_cube.as_string()));
+        for (i, goal) in self.goals.iter().enumerate() {
+            if goal.cube != self.current_cube {
+                continue;
+            }
+            let mut status = match (goal.achieved, goal.deadline) {
+                (Some(achieved), _) => {
+                    fl!("goal-achieved-on", date = goal::deadline_date(achieved))
+                }
+                (None, Some(deadline)) if goal.is_missed(now) => {
+                    fl!("goal-missed", date = goal::deadline_date(deadline))
+                }
+                (None, Some(deadline)) => fl!("goal-due", date = goal::deadline_date(deadline)),
+                (None, None) => String::new(),
+            };
+            let mut bar = None;
+            if let Some(record) = self.goal_record() {
+                let progress = goal.progress(record);
+                let current = match (goal.kind, progress.current) {
+                    (Kind::Solves, Some(count)) => {
+                        fl!("goal-solves-done", count = count, target = goal.target)
+                    }
+                    (_, Some(value)) => goal.cube.format_result(value, self.precision),
+                    (_, None) => String::from("N/A"),
+                };
+                status = match status.is_empty() {
+                    true => current,
+                    false => format!("{current} · {status}"),
+                };
+                bar = Some(widget::progress_bar(
+                    0.0..=1.0,
+                    match goal.achieved {
+                        Some(_) => 1.0,
+                        None => progress.fraction,
+                    },
+                ));
+            }
+            goals = goals.add(
+                settings::item::builder(self.goal_title(goal))
+                    .description(status)
+                    .control(
+                        widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
+                            .on_press(Message::RemoveGoal(i)),
+                    ),
+            );
+            if let Some(bar) = bar {
+                goals = goals.add(bar);
+            }
+        }
+
+        let kind = Kind::ALL[self.goal_kind];
+        let placeholder = match kind {
+            Kind::Solves => "100".to_string(),
+            _ if self.current_cube == Cube::ThreeFmc => "30".to_string(),
+            _ => "15.00".to_string(),
+        };
+        let mut content = widget::column([])
+            .spacing(active_theme.cosmic().space_m())
+            .push(widget::text::body(fl!("goals-description")));
+        if self.goal_record().is_none() {
+            content = content.push(widget::text::caption(fl!("goals-training")));
+        }
+        content = content.push(goals).push(
+            settings::section()
+                .title(fl!("goal-new"))
+                .add(settings::item(
+                    fl!("goal-kind"),
+                    dropdown(
+                        &self.goal_kind_options_labels,
+                        Some(self.goal_kind),
+                        Message::GoalKindUpdate,
+                    ),
+                ))
+                .add(settings::item(
+                    match kind {
+                        Kind::Solves => fl!("goal-target-solves"),
+                        _ => fl!("goal-target-result"),
+                    },
+                    widget::text_input(placeholder, &self.goal_target_input)
+                        .on_input(Message::GoalTargetInput)
+                        .width(120),
+                ))
+                .add(
+                    settings::item::builder(fl!("goal-deadline"))
+                        .description(fl!("goal-deadline-description"))
+                        .control(
+                            widget::text_input("2026-12-01", &self.goal_deadline_input)
+                                .on_input(Message::GoalDeadlineInput)
+                                .width(120),
+                        ),
+                ),
+        );
+
+        if let Some(error) = &self.goal_error {
+            let error_color = active_theme.cosmic().destructive_color();
+            content = content.push(widget::text::body(error.clone()).class(
+                cosmic::style::Text::Color(cosmic::iced::Color::from_rgb(
+                    error_color.red,
+                    error_color.green,
+                    error_color.blue,
+                )),
+            ));
+        }
+
+        content
+            .push(widget::button::suggested(fl!("goal-add")).on_press(Message::AddGoal))
+            .into()
+    }
+
+    fn settings_page(&self) -> Element<'_, Message> {
+        let selected_precision = Precision::ALL.iter().position(|p| *p == self.precision);
+        let selected_template = Template::ALL.iter().position(|t| *t == self.share_template);
+
+        let timer = settings::section()
+            .title(fl!("timer"))
+            .add(
+                settings::item::builder(fl!("trigger"))
+                    .description(fl!("trigger-description"))
+                    .control(
+                        widget::button::standard(match self.binding_trigger {
+                            true => fl!("trigger-waiting"),
+                            false => self.trigger_label(),
+                        })
+                        .on_press(Message::BindTrigger),
+                    ),
+            )
+            .add(settings::item(
+                fl!("precision"),
+                dropdown(
+                    &self.precision_options_labels,
+                    selected_precision,
+                    Message::PrecisionUpdate,
+                ),
+            ))
+            .add(settings::item(
+                fl!("wca-rounding"),
+                widget::toggler(self.wca_rounding).on_toggle(Message::ToggleWcaRounding),
+            ))
+            .add(
+                settings::item::builder(fl!("inspection"))
+                    .description(fl!(
+                        "inspection-description",
+                        trigger = self.trigger_label()
+                    ))
+                    .control(widget::toggler(self.inspection).on_toggle(Message::ToggleInspection)),
+            )
+            .add(
+                settings::item::builder(fl!("phases"))
+                    .description(fl!("phases-description", trigger = self.trigger_label()))
+                    .control(dropdown(
+                        &self.phase_options_labels,
+                        Some(self.phases - 1),
+                        Message::PhasesUpdate,
+                    )),
+            );
+        let sharing = settings::section().title(fl!("sharing")).add(
+            settings::item::builder(fl!("share-template"))
+                .description(fl!("share-template-description"))
+                .control(dropdown(
+                    &self.share_template_options_labels,
+                    selected_template,
+                    Message::ShareTemplateUpdate,
+                )),
+        );
+
+        let selected_sound_theme = sound::Theme::ALL
+            .iter()
+            .position(|t| *t == self.sound_theme);
+        let mut sounds = settings::section()
+            .title(fl!("sounds"))
+            .add(
+                settings::item::builder(fl!("sound-cues"))
+                    .description(fl!("sound-cues-description"))
+                    .control(widget::toggler(self.sounds).on_toggle(Message::ToggleSounds)),
+            )
+            .add(settings::item(
+                fl!("sound-theme"),
+                dropdown(
+                    &self.sound_theme_options_labels,
+                    selected_sound_theme,
+                    Message::SoundThemeUpdate,
+                ),
+            ))
+            .add(settings::item(
+                fl!("volume"),
+                widget::slider(0..=100, self.volume, Message::VolumeUpdate).width(200),
+            ));
+        // Without speech-dispatcher there is nothing to read results aloud
+        if self.can_speak {
+            sounds = sounds.add(
+                settings::item::builder(fl!("speak-results"))
+                    .description(fl!("speak-results-description"))
+                    .control(
+                        widget::toggler(self.speak_results).on_toggle(Message::ToggleSpeakResults),
+                    ),
+            );
+        }
+
+        let overlay = settings::section()
+            .title(fl!("overlay"))
+            .add(
+                settings::item::builder(fl!("overlay-enabled"))
+                    .description(fl!(
+                        "overlay-description",
+                        url = format!("http://127.0.0.1:{}/", self.overlay_port)
+                    ))
+                    .control(
+                        widget::toggler(self.overlay_enabled).on_toggle(Message::ToggleOverlay),
+                    ),
+            )
+            .add(settings::item(
+                fl!("overlay-port"),
+                widget::text_input(overlay::DEFAULT_PORT.to_string(), &self.overlay_port_input)
+                    .on_input(Message::OverlayPortInput)
+                    .on_submit(|_| Message::OverlayPortSubmit)
+                    .width(100),
+            ));
+
+        let mut sync = settings::section().title(fl!("sync")).add(
+            settings::item::builder(fl!("sync-target"))
+                .description(fl!("sync-description"))
+                .control(dropdown(
+                    &self.sync_kind_options_labels,
+                    Some(self.sync_kind),
+                    Message::SyncKindUpdate,
+                )),
+        );
+        match self.sync_kind {
+            1 => {
+                let folder = match &self.sync_folder {
+                    Some(folder) => folder.display().to_string(),
+                    None => fl!("sync-folder-none"),
+                };
+                sync = sync.add(
+                    settings::item::builder(fl!("sync-folder-path"))
+                        .description(folder)
+                        .control(
+                            widget::button::standard(fl!("sync-folder-choose"))
+                                .on_press(Message::ChooseSyncFolder),
+                        ),
+                );
+            }
+            2 => {
+                sync = sync
+                    .add(settings::item(
+                        fl!("sync-url"),
+                        widget::text_input(
+                            "https://example.com/dav/tesseract",
+                            &self.sync_url_input,
+                        )
+                        .on_input(Message::SyncUrlInput)
+                        .width(250),
+                    ))
+                    .add(settings::item(
+                        fl!("sync-username"),
+                        widget::text_input("", &self.sync_username_input)
+                            .on_input(Message::SyncUsernameInput)
+                            .width(250),
+                    ))
+                    .add(settings::item(
+                        fl!("sync-password"),
+                        widget::secure_input("", &self.sync_password_input, None, true)
+                            .on_input(Message::SyncPasswordInput)
+                            .width(250),
+                    ));
+            }
+            _ => {}
+        }
+        let status = match (&self.sync_error, self.syncing, self.last_sync) {
+            (Some(error), _, _) => fl!("sync-failed", error = error.clone()),
+            (None, true, _) => fl!("sync-running"),
+            (None, false, Some(time)) => fl!("sync-last", time = sync_time(time)),
+            (None, false, None) => fl!("sync-never"),
+        };
+        sync = sync.add(
+            settings::item::builder(status).control(
+                widget::row([])
+                    .spacing(cosmic::theme::active().cosmic().space_xs())
+                    .push(
+                        widget::button::standard(fl!("sync-now")).on_press_maybe(
+                            (!self.syncing && self.sync_target != Target::Off)
+                                .then_some(Message::SyncNow),
+                        ),
+                    )
+                    .push(
+                        widget::button::suggested(fl!("sync-save"))
+                            .on_press(Message::SaveSyncTarget),
+                    ),
+            ),
+        );
+
+        widget::column([])
+            .spacing(cosmic::theme::active().cosmic().space_m())
+            .push(timer)
+            .push(sounds)
+            .push(sharing)
+            .push(overlay)
+            .push(sync)
+            .into()
+    }
+
+    fn training_page(&self) -> Element<'_, Message> {
+        let selected_subset = self
+            .training
+            .and_then(|subset| Subset::ALL.iter().position(|s| *s == subset))
+            .map_or(0, |i| i + 1);
+
+        let mut content = widget::column([])
+            .spacing(cosmic::theme::active().cosmic().space_m())
+            .push(widget::text::body(fl!("training-description")))
+            .push(settings::section().add(settings::item(
+                fl!("training-case-group"),
+                dropdown(
+                    &self.training_options_labels,
+                    Some(selected_subset),
+                    Message::TrainingSubset,
+                ),
+            )));
+
+        if let Some(subset) = self.training {
+            let mut cases = settings::section().title(fl!("training-cases"));
+            for case in subset.cases() {
+                cases = cases.add(settings::item(
+                    case.name,
+                    widget::toggler(self.training_cases.iter().any(|c| c == case.name)).on_toggle(
+                        |selected| Message::TrainingCase(case.name.to_string(), selected),
+                    ),
+                ));
+            }
+            content = content
+                .push(
+                    widget::row([])
+                        .spacing(cosmic::theme::active().cosmic().space_s())
+                        .push(
+                            widget::button::standard(fl!("select-all"))
+                                .on_press(Message::TrainingSelectAll(true)),
+                        )
+                        .push(
+                            widget::button::standard(fl!("select-none"))
+                                .on_press(Message::TrainingSelectAll(false)),
+                        ),
+                )
+                .push(cases);
+        }
+
+        content.into()
+    }
+
+    // Training only applies to the 3x3x3
+    fn training_subset(&self) -> Option<Subset> {
+        self.training.filter(|_| self.current_cube == Cube::Three)
+    }
+
+    // Trainer results are recognition times, whichever puzzle is selected
+    fn record_cube(&self) -> Cube {
+        match self.trainer {
+            Some(_) => Cube::Three,
+            None => self.current_cube.clone(),
+        }
+    }
+
+    fn record_key(&self) -> &str {
+        if let Some(subset) = self.trainer {
+            return trainer::config_key(subset);
+        }
+        match self.training_subset() {
+            Some(subset) => subset.config_key(),
+            None => self.current_cube.config_key(),
+        }
+    }
+
+    fn next_drill(&mut self) {
+        let previous = self.drill.as_ref().map(|drill| drill.case.name);
+        self.drill = self
+            .trainer
+            .and_then(|subset| trainer::next_case(subset, &self.record, previous))
+            .map(Drill::new);
+    }
+
+    fn rescramble(&mut self) {
+        if !self.current_scramble.is_empty() {
+            self.previous_scrambles.push((
+                std::mem::take(&mut self.current_scramble),
+                self.current_case.take(),
+            ));
+            if self.previous_scrambles.len() > MAX_PREVIOUS_SCRAMBLES {
+                self.previous_scrambles.remove(0);
+            }
+        }
+
+        if let Some((scramble, case)) = self.skipped_scrambles.pop() {
+            self.current_case = case;
+            self.current_scramble = scramble;
+        } else if let Some(scramble) = self.custom_scrambles.pop_front() {
+            self.current_case = None;
+            self.current_scramble = scramble;
+        } else if let Some((scramble, case)) = self.scramble_queue.next(&self.queue_key()) {
+            self.current_case = case;
+            self.current_scramble = scramble;
+        } else if let Some(subset) = self.training_subset() {
+            // Only generated here when the background queue has nothing ready yet. With no cases
+            // selected there is nothing to set up, which the scramble area says
+            let (case, scramble) = training::generate(subset, &self.training_cases).unzip();
+            self.current_case = case;
+            self.current_scramble = scramble.unwrap_or_default();
+        } else {
+            // Only generated here when the background queue has nothing ready yet
+            self.current_case = None;
+            self.current_scramble =
+                generate_scramble(None, Some(self.current_cube.scramble_type()))
+                    .unwrap_or_default();
+        }
+        self.reset_virtual_cube();
+    }
+
+    // The scramble that comes after the current one, if it is known already
+    fn next_scramble(&self) -> Option<&Vec<String>> {
+        if let Some((scramble, _)) = self.skipped_scrambles.last() {
+            return Some(scramble);
+        }
+        if let Some(scramble) = self.custom_scrambles.front() {
+            return Some(scramble);
+        }
+        self.scramble_queue.peek(&self.queue_key())
+    }
+
+    // Scrambles are queued by type, and training ones by subset and selected cases, so changing
+    // the selection never leaves older ones to come up
+    fn queue_key(&self) -> String {
+        match self.training_subset() {
+            Some(subset) => format!("{subset:?}: {}", self.training_cases.join(", ")),
+            None => self.current_cube.scramble_type(),
+        }
+    }
+
+    // Whether any selected training case belongs to `subset`
+    fn training_cases_selected(&self, subset: Subset) -> bool {
+        subset
+            .cases()
+            .iter()
+            .any(|case| self.training_cases.iter().any(|c| c == case.name))
+    }
+
+    // Progress is measured against the puzzle's own record, not a training one
+    fn goal_record(&self) -> Option<&Record> {
+        (self.record_key() == self.current_cube.config_key()).then_some(&self.record)
+    }
+
+    fn check_goals(&mut self) -> Task<cosmic::Action<Message>> {
+        if self.goal_record().is_none() {
+            return Task::none();
+        }
+        let now = unix_now();
+        let mut achieved = vec![];
+        for goal in self.goals.iter_mut() {
+            if goal.cube == self.current_cube && goal.check(&self.record, now) {
+                achieved.push(goal.clone());
+            }
+        }
+        if achieved.is_empty() {
+            return Task::none();
+        }
+        let _ = self.config.set("goals", &self.goals);
+        Task::batch(achieved.iter().map(|goal| {
+            let title = self.goal_title(goal);
+            self.toasts
+                .push(Toast::new(fl!("goal-achieved", goal = title)))
+                .map(cosmic::Action::App)
+        }))
+    }
+
+    fn parse_goal_target(&self, kind: Kind) -> Option<u32> {
+        let input = self.goal_target_input.trim();
+        match kind {
+            Kind::Solves => input.parse::<u32>().ok().filter(|count| *count > 0),
+            // FMC results are stored as a hundred times the move count
+            _ if self.current_cube == Cube::ThreeFmc => input
+                .parse::<f32>()
+                .ok()
+                .filter(|moves| *moves > 0.0)
+                .map(|moves| (moves * 100.0).round() as u32),
+            _ => match timer::parse_entry(input)? {
+                Entry::Time(time) if time > 0 => Some(time),
+                _ => None,
+            },
+        }
+    }
+
+    fn goal_title(&self, goal: &Goal) -> String {
+        let target = goal.cube.format_result(goal.target, self.precision);
+        match goal.kind {
+            Kind::Single => fl!("goal-single", target = target),
+            Kind::Ao5 => fl!("goal-average", target = target, count = 5),
+            Kind::Ao12 => fl!("goal-average", target = target, count = 12),
+            Kind::Ao100 => fl!("goal-average", target = target, count = 100),
+            Kind::Solves => fl!("goal-solves", count = goal.target),
+        }
+    }
+
+    // Keep scrambles for the current puzzle generating in the background until enough are ready
+    fn fill_scramble_queue(&mut self) -> Task<cosmic::Action<Message>> {
+        let key = self.queue_key();
+        let training = self.training_subset();
+        // Nothing can be set up until a case is selected
+        if training.is_some_and(|subset| !self.training_cases_selected(subset)) {
+            return Task::none();
+        }
+        Task::batch((0..self.scramble_queue.request(&key)).map(|_| {
+            let generated = match training {
+                Some(subset) => Task::future(scramble::generate_training(
+                    key.clone(),
+                    subset,
+                    self.training_cases.clone(),
+                )),
+                None => Task::future(scramble::generate(key.clone())),
+            };
+            generated.map(|(key, scramble, case)| {
+                cosmic::Action::App(Message::ScrambleGenerated(key, scramble, case))
+            })
+        }))
+    }
+    // Set the virtual cube up with the current scramble, abandoning any solve on it
+    fn reset_virtual_cube(&mut self) {
+        if self.virtual_state.is_some() && self.timer.status == Status::Running {
+            self.timer.status = Status::Stopped;
+            self.stopwatch.stop();
+        }
+        self.virtual_state = self
+            .virtual_cube
+            .then(|| VirtualCube::new(self.current_cube.size(), &self.current_scramble))
+            .flatten();
+    }
+    fn load_record(&mut self) {
+        self.close_solve();
+        self.record_read_only = false;
+        self.record = match self.config.get::<Record>(self.record_key()) {
+            Ok(record) if record.version > record::VERSION => {
+                // Saving would drop whatever the newer version added
+                self.record_read_only = true;
+                self.record_error = Some(fl!("record-newer"));
+                record
+            }
+            Ok(record) if record.version < record::VERSION => {
+                self.backup(Reason::BeforeUpgrade);
+                let record = record.migrate();
+                let result = self.config.set(self.record_key(), &record);
+                self.report(result);
+                record
+            }
+            Ok(record) => record,
+            Err(cosmic_config::Error::NotFound) => Record::default(),
+            Err(err) => {
+                // Keep the stored data for a fixed version of the app rather than overwrite it
+                tracing::error!("Failed to load record: {err}");
+                self.record_read_only = true;
+                self.record_error = Some(fl!("record-unreadable", error = err.to_string()));
+                Record::default()
+            }
+        };
+        self.sync_comment();
+    }
+    fn save_record(&mut self) {
+        if self.record_read_only {
+            return;
+        }
+        let result = self.config.set(self.record_key(), &self.record);
+        self.report(result);
+        self.backup_due = true;
+    }
+
+    // Keep write failures on screen rather than losing them
+    fn report(&mut self, result: Result<(), cosmic_config::Error>) {
+        if let Err(err) = result {
+            tracing::error!("Failed to save: {err}");
+            self.record_error = Some(fl!("write-failed", error = err.to_string()));
+        }
+    }
+
+    // Every record in the config, whichever puzzle or training mode it belongs to
+    fn all_records(&self) -> Vec<(String, Record)> {
+        self.cube_options
+            .iter()
+            .map(|cube| cube.config_key())
+            .chain(Subset::ALL.iter().map(|subset| subset.config_key()))
+            .chain(
+                trainer::SUBSETS
+                    .iter()
+                    .map(|&subset| trainer::config_key(subset)),
+            )
+            .filter_map(|key| Some((key.to_string(), self.config.get::<Record>(key).ok()?)))
+            .collect()
+    }
+
+    fn backup(&mut self, reason: Reason) {
+        let result = self.backups.take(reason, self.all_records());
+        if result.is_ok() {
+            self.backup_due = false;
+        }
+        self.report(result);
+    }
+
+    // Apply a change to the current record, save it and make it undoable
+    fn edit_record(&mut self, edit: impl FnOnce(&mut Record)) {
+        self.edit_records(vec![], edit);
+    }
+
+    // As `edit_record`, undone together with changes already saved to other records
+    fn edit_records(&mut self, others: Vec<Change>, edit: impl FnOnce(&mut Record)) {
+        let before = self.record.clone();
+        edit(&mut self.record);
+        self.record.recalculate();
+
+        if let Some(remote) = &self.remote
+            && self.record.solves.len() > before.solves.len()
+            && let Some(solve) = self.record.solves.first()
+        {
+            remote.solve_added(
+                self.record_cube().as_string(),
+                solve.result(Precision::Thousandths),
+                solve.scramble.join(" "),
+            );
+        }
+
+        self.save_record();
+        self.sync_comment();
+        let mut changes = others;
+        changes.push(Change::between(
+            self.record_key().to_string(),
+            &before,
+            &self.record,
+        ));
+        self.history.push(Edit { changes });
+    }
+
+    // Undo or redo a change from the history on its record, which may belong to another puzzle
+    fn restore_record(&mut self, key: &str, restore: impl FnOnce(&mut Record)) {
+        if key == self.record_key() {
+            restore(&mut self.record);
+            self.sync_comment();
+            self.save_record();
+        } else {
+            let result = self.config.get::<Record>(key).and_then(|mut record| {
+                restore(&mut record);
+                self.config.set(key, &record)
+            });
+            self.report(result);
+            self.backup_due = true;
+        }
+    }
+
+    fn undo_toast(&mut self, message: String) -> Task<cosmic::Action<Message>> {
+        self.toasts
+            .push(Toast::new(message).action(fl!("undo"), Message::ToastUndo))
+            .map(cosmic::Action::App)
+    }
+
+    // The comment box always edits the most recent solve, the detail page the selected one
+    fn sync_comment(&mut self) {
+        self.comment_input = self
+            .record
+            .solves
+            .first()
+            .and_then(|solve| solve.comment.clone())
+            .unwrap_or_default();
+        self.detail_comment = self
+            .selected_solve
+            .and_then(|id| self.record.solve(id))
+            .and_then(|solve| solve.comment.clone())
+            .unwrap_or_default();
+    }
+
+    fn close_solve(&mut self) {
+        self.selected_solve = None;
+        if self.context_page == ContextPage::Solve {
+            self.core.window.show_context = false;
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum ContextPage {
+    #[default]
+    About,
+    Backups,
+    Calendar,
+    Goals,
+    Performance,
+    Scrambles,
+    Settings,
+    Solve,
+    Training,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MenuAction {
+    About,
+    Backups,
+    Calendar,
+    CustomScrambles,
+    Goals,
+    Performance,
+    Settings,
+    Training,
+    ToggleManualEntry,
+    ToggleVirtualCube,
+    ToggleTouchPads,
+    ToggleHideTimer,
+    ToggleFocusMode,
+    ToggleTrainer,
+    Undo,
+    Redo,
+}
+
+impl menu::action::MenuAction for MenuAction {
+    type Message = Message;
+
+    fn message(&self) -> Self::Message {
+        match self {
             MenuAction::About => Message::ToggleContextPage(ContextPage::About),
+            MenuAction::Backups => Message::ToggleContextPage(ContextPage::Backups),
+            MenuAction::Calendar => Message::ToggleContextPage(ContextPage::Calendar),
+            MenuAction::CustomScrambles => Message::ToggleContextPage(ContextPage::Scrambles),
+            MenuAction::Goals => Message::ToggleContextPage(ContextPage::Goals),
+            MenuAction::Performance => Message::ToggleContextPage(ContextPage::Performance),
+            MenuAction::Settings => Message::ToggleContextPage(ContextPage::Settings),
+            MenuAction::Training => Message::ToggleContextPage(ContextPage::Training),
+            MenuAction::ToggleManualEntry => Message::ToggleManualEntry,
+            MenuAction::ToggleVirtualCube => Message::ToggleVirtualCube,
+            MenuAction::ToggleTouchPads => Message::ToggleTouchPads,
+            MenuAction::ToggleHideTimer => Message::ToggleHideTimer,
+            MenuAction::ToggleFocusMode => Message::ToggleFocusMode,
+            MenuAction::ToggleTrainer => Message::ToggleTrainer,
+            MenuAction::Undo => Message::Undo,
+            MenuAction::Redo => Message::Redo,
         }
     }
 }
 
+fn key_binds() -> HashMap<menu::KeyBind, MenuAction> {
+    let mut key_binds = HashMap::new();
+    key_binds.insert(
+        menu::KeyBind {
+            modifiers: vec![menu::key_bind::Modifier::Ctrl],
+            key: keyboard::Key::Character("z".into()),
+        },
+        MenuAction::Undo,
+    );
+    key_binds.insert(
+        menu::KeyBind {
+            modifiers: vec![
+                menu::key_bind::Modifier::Ctrl,
+                menu::key_bind::Modifier::Shift,
+            ],
+            key: keyboard::Key::Character("z".into()),
+        },
+        MenuAction::Redo,
+    );
+    key_binds
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+// When a sync finished, in the local time zone
+fn sync_time(time: u64) -> String {
+    jiff::Timestamp::from_second(time as i64)
+        .map(|time| {
+            time.to_zoned(jiff::tz::TimeZone::system())
+                .strftime("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn all_cases(subset: Option<Subset>) -> Vec<String> {
+    subset
+        .map(|subset| subset.cases().iter().map(|c| c.name.to_string()).collect())
+        .unwrap_or_default()
+}
+
 pub fn build_about() -> About {
     About::default()
         .developers([("Jonathan Capps", "cappsy@gmail.com")])
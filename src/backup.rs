@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Snapshots of every solving record, kept in their own config so they can be restored.
+
+use crate::record::Record;
+use cosmic::cosmic_config::{self, Config, ConfigGet, ConfigSet};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime};
+
+/// How often a snapshot is taken while records keep changing.
+pub const INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// Snapshots are stored in a ring of this many slots, the oldest being overwritten
+const SLOTS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Reason {
+    Scheduled,
+    Manual,
+    // No longer taken, but older snapshots may have been
+    BeforeRemove,
+    BeforeClear,
+    BeforeRestore,
+    BeforeUpgrade,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub timestamp: u64,
+    pub reason: Reason,
+    pub records: Vec<(String, Record)>,
+}
+
+/// What the restore list shows of a snapshot, without loading its records.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Summary {
+    pub slot: usize,
+    pub timestamp: u64,
+    pub reason: Reason,
+    pub solves: usize,
+}
+
+pub struct Backups {
+    config: Config,
+    /// Newest first.
+    pub snapshots: Vec<Summary>,
+}
+impl Backups {
+    pub fn new(config: Config) -> Backups {
+        let snapshots = config.get::<Vec<Summary>>("snapshots").unwrap_or_default();
+        Backups { config, snapshots }
+    }
+
+    pub fn take(
+        &mut self,
+        reason: Reason,
+        records: Vec<(String, Record)>,
+    ) -> Result<(), cosmic_config::Error> {
+        let slot = (0..SLOTS)
+            .find(|slot| !self.snapshots.iter().any(|s| s.slot == *slot))
+            .or_else(|| self.snapshots.last().map(|s| s.slot))
+            .unwrap_or_default();
+        let snapshot = Snapshot {
+            timestamp: SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            reason,
+            records,
+        };
+        self.config.set(&format!("snapshot_{slot}"), &snapshot)?;
+
+        self.snapshots.retain(|s| s.slot != slot);
+        self.snapshots.insert(
+            0,
+            Summary {
+                slot,
+                timestamp: snapshot.timestamp,
+                reason,
+                solves: snapshot.records.iter().map(|(_, r)| r.solves.len()).sum(),
+            },
+        );
+        self.config.set("snapshots", &self.snapshots)
+    }
+
+    pub fn load(&self, slot: usize) -> Result<Snapshot, cosmic_config::Error> {
+        self.config.get::<Snapshot>(&format!("snapshot_{slot}"))
+    }
+}
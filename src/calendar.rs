@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Solves grouped by the day they were done on, for a practice calendar and streaks.
+
+use crate::record::Solve;
+use jiff::civil::Date;
+use jiff::tz::TimeZone;
+use std::collections::BTreeMap;
+
+/// Solves done on one day, counted from midnight in the time zone used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Day {
+    pub date: Date,
+    /// Index of each solve in the record, newest first.
+    pub solves: Vec<usize>,
+    /// Mean of the results that aren't DNFs.
+    pub mean: Option<u32>,
+    pub best: Option<u32>,
+}
+
+/// Every day with at least one solve, oldest first. Solves without a timestamp are left out.
+pub fn days(solves: &[Solve], tz: &TimeZone) -> Vec<Day> {
+    let mut dates: BTreeMap<Date, Vec<usize>> = BTreeMap::new();
+    for (i, solve) in solves.iter().enumerate() {
+        let Some(date) = solve.timestamp.and_then(|timestamp| date(timestamp, tz)) else {
+            continue;
+        };
+        dates.entry(date).or_default().push(i);
+    }
+    dates
+        .into_iter()
+        .map(|(date, indices)| {
+            let values: Vec<u32> = indices
+                .iter()
+                .map(|&i| &solves[i])
+                .filter(|solve| !solve.dnf)
+                .map(Solve::value)
+                .collect();
+            Day {
+                date,
+                mean: (!values.is_empty()).then(|| {
+                    (values.iter().map(|&v| v as u64).sum::<u64>() / values.len() as u64) as u32
+                }),
+                best: values.iter().copied().min(),
+                solves: indices,
+            }
+        })
+        .collect()
+}
+
+/// The date of a Unix timestamp in `tz`.
+pub fn date(timestamp: u64, tz: &TimeZone) -> Option<Date> {
+    jiff::Timestamp::from_second(timestamp as i64)
+        .ok()
+        .map(|time| time.to_zoned(tz.clone()).date())
+}
+
+/// Current and longest runs of consecutive days practised, from `days` in date order. The
+/// current streak still counts until a whole day goes by without a solve.
+pub fn streaks(days: &[Day], today: Date) -> (usize, usize) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<Date> = None;
+    for day in days {
+        run = match previous {
+            Some(date) if date.tomorrow().ok() == Some(day.date) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day.date);
+    }
+    let current = match previous {
+        Some(date) if date == today || date.tomorrow().ok() == Some(today) => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::solve;
+    use jiff::civil::date as ymd;
+
+    const DAY: u64 = 86_400;
+
+    #[test]
+    fn solves_are_grouped_by_day() {
+        // 1 January 2026, midnight UTC
+        let start = 1_767_225_600;
+        let mut dnf = solve(5_000, start + 2 * DAY);
+        dnf.dnf = true;
+        let solves = vec![
+            dnf,
+            solve(12_000, start + DAY + 60),
+            solve(10_000, start + 60),
+            solve(14_000, start + 30),
+        ];
+        let days = days(&solves, &TimeZone::UTC);
+        assert_eq!(days.len(), 3);
+        assert_eq!(days[0].date, ymd(2026, 1, 1));
+        assert_eq!(days[0].solves, vec![2, 3]);
+        assert_eq!(days[0].mean, Some(12_000));
+        assert_eq!(days[0].best, Some(10_000));
+        assert_eq!(days[2].mean, None);
+    }
+
+    #[test]
+    fn streaks_need_consecutive_days() {
+        let day = |d| Day {
+            date: ymd(2026, 3, d),
+            solves: vec![0],
+            mean: None,
+            best: None,
+        };
+        let days = vec![day(1), day(2), day(3), day(5), day(6)];
+        assert_eq!(streaks(&days, ymd(2026, 3, 6)), (2, 3));
+        // Today hasn't been practised yet, but the streak isn't lost until tomorrow
+        assert_eq!(streaks(&days, ymd(2026, 3, 7)), (2, 3));
+        assert_eq!(streaks(&days, ymd(2026, 3, 8)), (0, 3));
+        assert_eq!(streaks(&[], ymd(2026, 3, 8)), (0, 0));
+    }
+}
@@ -0,0 +1,804 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! A sticker-level model of an NxN cube and a parser for WCA move notation, used to check
+//! scrambles and solutions.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Face {
+    U,
+    R,
+    F,
+    D,
+    L,
+    B,
+}
+impl Face {
+    pub const ALL: [Face; 6] = [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+    fn from_char(c: char) -> Option<Face> {
+        match c {
+            'U' => Some(Face::U),
+            'R' => Some(Face::R),
+            'F' => Some(Face::F),
+            'D' => Some(Face::D),
+            'L' => Some(Face::L),
+            'B' => Some(Face::B),
+            _ => None,
+        }
+    }
+    pub fn as_char(&self) -> char {
+        match self {
+            Face::U => 'U',
+            Face::R => 'R',
+            Face::F => 'F',
+            Face::D => 'D',
+            Face::L => 'L',
+            Face::B => 'B',
+        }
+    }
+    pub fn opposite(&self) -> Face {
+        match self {
+            Face::U => Face::D,
+            Face::R => Face::L,
+            Face::F => Face::B,
+            Face::D => Face::U,
+            Face::L => Face::R,
+            Face::B => Face::F,
+        }
+    }
+    // Axis (0 = x, 1 = y, 2 = z) and direction of the face's outward normal
+    fn axis(&self) -> (usize, i32) {
+        match self {
+            Face::R => (0, 1),
+            Face::L => (0, -1),
+            Face::U => (1, 1),
+            Face::D => (1, -1),
+            Face::F => (2, 1),
+            Face::B => (2, -1),
+        }
+    }
+    fn normal(&self) -> [i32; 3] {
+        let (axis, dir) = self.axis();
+        let mut normal = [0; 3];
+        normal[axis] = dir;
+        normal
+    }
+    fn from_normal(normal: [i32; 3]) -> Face {
+        Face::ALL
+            .into_iter()
+            .find(|face| face.normal() == normal)
+            .unwrap_or(Face::U)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    /// Turn of the `depth` outermost layers on a face, in clockwise quarter turns.
+    Turn {
+        face: Face,
+        depth: usize,
+        amount: u8,
+    },
+    /// Turn of every inner layer, following L (M), D (E) or F (S).
+    Slice { face: Face, amount: u8 },
+    /// Whole cube rotation following R (x), U (y) or F (z).
+    Rotation { face: Face, amount: u8 },
+}
+impl Move {
+    /// Read a move in WCA notation: a face turn such as `R`, `Rw` or `3Rw`, a lowercase wide
+    /// turn such as `r`, a slice move (`M`, `E`, `S`) or a rotation (`x`, `y`, `z`), followed
+    /// by nothing, `'`, `2` or `2'`.
+    pub fn parse(token: &str) -> Option<Move> {
+        let (body, amount) = if let Some(body) = token.strip_suffix("2'") {
+            (body, 2)
+        } else if let Some(body) = token.strip_suffix('2') {
+            (body, 2)
+        } else if let Some(body) = token.strip_suffix('\'') {
+            (body, 3)
+        } else {
+            (token, 1)
+        };
+
+        let rotation = |face| Some(Move::Rotation { face, amount });
+        let slice = |face| Some(Move::Slice { face, amount });
+        match body {
+            "x" => return rotation(Face::R),
+            "y" => return rotation(Face::U),
+            "z" => return rotation(Face::F),
+            "M" => return slice(Face::L),
+            "E" => return slice(Face::D),
+            "S" => return slice(Face::F),
+            _ => {}
+        }
+
+        // Number of layers for a wide turn, as in 3Rw
+        let digits = body.len() - body.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let (layers, body) = body.split_at(digits);
+        let mut chars = body.chars();
+        let first = chars.next()?;
+
+        let (face, depth) = match (layers, chars.as_str()) {
+            ("", "") if first.is_ascii_lowercase() => {
+                (Face::from_char(first.to_ascii_uppercase())?, 2)
+            }
+            ("", "") => (Face::from_char(first)?, 1),
+            ("", "w") => (Face::from_char(first)?, 2),
+            (layers, "w") if !layers.starts_with('0') => (
+                Face::from_char(first)?,
+                layers.parse::<usize>().ok().filter(|&depth| depth >= 2)?,
+            ),
+            _ => return None,
+        };
+        Some(Move::Turn {
+            face,
+            depth,
+            amount,
+        })
+    }
+
+    /// Whether the move can be made on a cube of `size`, leaving at least one layer unturned.
+    pub fn fits(&self, size: usize) -> bool {
+        match *self {
+            Move::Turn { depth, .. } => depth < size,
+            Move::Slice { .. } => size >= 3,
+            Move::Rotation { .. } => true,
+        }
+    }
+
+    pub fn amount(&self) -> u8 {
+        match *self {
+            Move::Turn { amount, .. }
+            | Move::Slice { amount, .. }
+            | Move::Rotation { amount, .. } => amount,
+        }
+    }
+
+    fn with_amount(&self, amount: u8) -> Move {
+        match *self {
+            Move::Turn { face, depth, .. } => Move::Turn {
+                face,
+                depth,
+                amount,
+            },
+            Move::Slice { face, .. } => Move::Slice { face, amount },
+            Move::Rotation { face, .. } => Move::Rotation { face, amount },
+        }
+    }
+
+    pub fn inverse(&self) -> Move {
+        self.with_amount((4 - self.amount() % 4) % 4)
+    }
+
+    /// Where a sticker at `pos`, facing `normal`, ends up after this move on a cube of `size`.
+    pub fn transform(&self, size: usize, pos: [i32; 3], normal: [i32; 3]) -> ([i32; 3], [i32; 3]) {
+        let outer = size as i32 - 1;
+        // Layers are measured towards the face, in the same doubled coordinates as stickers
+        let (face, from, to) = match *self {
+            Move::Turn { face, depth, .. } => {
+                (face, outer - 2 * (depth.min(size) as i32 - 1), outer)
+            }
+            Move::Slice { face, .. } => (face, 2 - outer, outer - 2),
+            Move::Rotation { face, .. } => (face, -outer, outer),
+        };
+        let (axis, dir) = face.axis();
+        let layer = pos[axis] * dir;
+        if layer < from || layer > to {
+            return (pos, normal);
+        }
+
+        // Clockwise seen from the face is a negative rotation about a positive axis
+        let amount = self.amount() % 4;
+        let quarter_turns = if dir > 0 { (4 - amount) % 4 } else { amount };
+        let (mut pos, mut normal) = (pos, normal);
+        for _ in 0..quarter_turns {
+            pos = rotate(pos, axis);
+            normal = rotate(normal, axis);
+        }
+        (pos, normal)
+    }
+}
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Move::Turn { face, depth: 1, .. } => write!(f, "{}", face.as_char())?,
+            Move::Turn { face, depth: 2, .. } => write!(f, "{}w", face.as_char())?,
+            Move::Turn { face, depth, .. } => write!(f, "{}{}w", depth, face.as_char())?,
+            Move::Slice { face, .. } => match face {
+                Face::D | Face::U => write!(f, "E")?,
+                Face::F | Face::B => write!(f, "S")?,
+                _ => write!(f, "M")?,
+            },
+            Move::Rotation { face, .. } => match face {
+                Face::U | Face::D => write!(f, "y")?,
+                Face::F | Face::B => write!(f, "z")?,
+                _ => write!(f, "x")?,
+            },
+        }
+        match self.amount() % 4 {
+            2 => write!(f, "2"),
+            3 => write!(f, "'"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A token that is not a move on the cube being scrambled, and the character it starts at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub token: String,
+    pub position: usize,
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" at character {}", self.token, self.position + 1)
+    }
+}
+
+/// Read a whitespace separated sequence of moves for a cube of `size`, rejecting anything
+/// that isn't a move or that turns every layer.
+pub fn parse_moves(text: &str, size: usize) -> Result<Vec<Move>, ParseError> {
+    let mut moves = vec![];
+    let mut start = None;
+    // A trailing space closes the last token
+    for (position, (i, c)) in text.char_indices().chain([(text.len(), ' ')]).enumerate() {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some((position, i)),
+            (Some((position, from)), true) => {
+                let token = &text[from..i];
+                match Move::parse(token).filter(|mv| mv.fits(size)) {
+                    Some(mv) => moves.push(mv),
+                    None => {
+                        return Err(ParseError {
+                            token: token.to_string(),
+                            position,
+                        });
+                    }
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    Ok(moves)
+}
+
+/// The sequence that undoes `moves`.
+pub fn invert(moves: &[Move]) -> Vec<Move> {
+    moves.iter().rev().map(|mv| mv.inverse()).collect()
+}
+
+/// Rewrite a 3x3x3 sequence using only outer face turns, as it would be applied without
+/// rotating the cube, and merge consecutive turns of the same face.
+pub fn face_turns(moves: &[Move]) -> Vec<Move> {
+    // Physical face currently sitting where the sequence expects each face
+    let mut orientation = Face::ALL;
+    let mut result: Vec<Move> = vec![];
+
+    let physical = |orientation: &[Face; 6], face: Face| {
+        orientation[Face::ALL.iter().position(|f| *f == face).unwrap_or(0)]
+    };
+
+    for mv in moves {
+        let (turns, rotation) = match *mv {
+            Move::Turn {
+                face,
+                depth: 1,
+                amount,
+            } => (vec![(face, amount)], None),
+            // A wide turn is the opposite face turned the same way, plus a rotation
+            Move::Turn { face, amount, .. } => {
+                (vec![(face.opposite(), amount)], Some((face, amount)))
+            }
+            Move::Slice { face, amount } => (
+                vec![(face.opposite(), amount), (face, (4 - amount % 4) % 4)],
+                Some((face, amount)),
+            ),
+            Move::Rotation { face, amount } => (vec![], Some((face, amount))),
+        };
+
+        for (face, amount) in turns {
+            let face = physical(&orientation, face);
+            match result.last_mut() {
+                Some(Move::Turn {
+                    face: last,
+                    amount: last_amount,
+                    ..
+                }) if *last == face => {
+                    *last_amount = (*last_amount + amount) % 4;
+                    if *last_amount == 0 {
+                        result.pop();
+                    }
+                }
+                _ => result.push(Move::Turn {
+                    face,
+                    depth: 1,
+                    amount: amount % 4,
+                }),
+            }
+        }
+
+        if let Some((face, amount)) = rotation {
+            let (axis, dir) = face.axis();
+            // Undo the rotation on each face's normal to find where it now points
+            let quarter_turns = if dir > 0 {
+                amount % 4
+            } else {
+                (4 - amount % 4) % 4
+            };
+            let previous = orientation;
+            for (i, face) in Face::ALL.iter().enumerate() {
+                let mut normal = face.normal();
+                for _ in 0..quarter_turns {
+                    normal = rotate(normal, axis);
+                }
+                orientation[i] = physical(&previous, Face::from_normal(normal));
+            }
+        }
+    }
+
+    result.retain(|mv| mv.amount() % 4 != 0);
+    result
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Sticker {
+    // Cubie position in doubled coordinates, so every layer sits on an integer
+    pos: [i32; 3],
+    normal: [i32; 3],
+    color: Face,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CubeState {
+    size: usize,
+    stickers: Vec<Sticker>,
+}
+impl CubeState {
+    pub fn solved(size: usize) -> CubeState {
+        let outer = size as i32 - 1;
+        let coords: Vec<i32> = (0..size as i32).map(|i| 2 * i - outer).collect();
+        let mut stickers = Vec::with_capacity(6 * size * size);
+
+        for face in Face::ALL {
+            let (axis, dir) = face.axis();
+            let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+            for &i in &coords {
+                for &j in &coords {
+                    let mut pos = [0; 3];
+                    pos[axis] = dir * outer;
+                    pos[a] = i;
+                    pos[b] = j;
+                    stickers.push(Sticker {
+                        pos,
+                        normal: face.normal(),
+                        color: face,
+                    });
+                }
+            }
+        }
+
+        CubeState { size, stickers }
+    }
+
+    /// A cube of `size` after a scramble, if every move in it is understood.
+    pub fn scrambled(size: usize, scramble: &[String]) -> Option<CubeState> {
+        let mut state = CubeState::solved(size);
+        for token in scramble {
+            state.apply(&Move::parse(token).filter(|mv| mv.fits(size))?);
+        }
+        Some(state)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn apply(&mut self, mv: &Move) {
+        for sticker in self.stickers.iter_mut() {
+            (sticker.pos, sticker.normal) = mv.transform(self.size, sticker.pos, sticker.normal);
+        }
+    }
+
+    pub fn apply_all<'a>(&mut self, moves: impl IntoIterator<Item = &'a Move>) {
+        for mv in moves {
+            self.apply(mv);
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.stickers.iter().all(|sticker| {
+            self.stickers
+                .iter()
+                .filter(|other| other.normal == sticker.normal)
+                .all(|other| other.color == sticker.color)
+        })
+    }
+
+    /// Whether every sticker on the given layers, counted from the D face, is back in place.
+    pub fn layers_solved(&self, layers: usize) -> bool {
+        let outer = self.size as i32 - 1;
+        let top = outer - 2 * (self.size as i32 - layers as i32);
+        self.stickers
+            .iter()
+            .filter(|sticker| sticker.pos[1] <= top)
+            .all(|sticker| sticker.color == Face::from_normal(sticker.normal))
+    }
+
+    /// Current position of the piece made up of the given colours.
+    pub fn locate(&self, colors: &[Face]) -> Option<[i32; 3]> {
+        self.stickers
+            .iter()
+            .filter(|sticker| sticker.color == colors[0])
+            .map(|sticker| sticker.pos)
+            .find(|&pos| {
+                let at_pos: Vec<Face> = self
+                    .stickers
+                    .iter()
+                    .filter(|other| other.pos == pos)
+                    .map(|other| other.color)
+                    .collect();
+                at_pos.len() == colors.len() && colors.iter().all(|color| at_pos.contains(color))
+            })
+    }
+
+    /// Home position of the piece made up of the given colours.
+    pub fn home(&self, colors: &[Face]) -> [i32; 3] {
+        let outer = self.size as i32 - 1;
+        let mut pos = [0; 3];
+        for color in colors {
+            let (axis, dir) = color.axis();
+            pos[axis] = dir * outer;
+        }
+        pos
+    }
+
+    /// Colour of the sticker at a position, facing out of `face`.
+    pub fn color_at(&self, pos: [i32; 3], face: Face) -> Option<Face> {
+        self.stickers
+            .iter()
+            .find(|sticker| sticker.pos == pos && sticker.normal == face.normal())
+            .map(|sticker| sticker.color)
+    }
+
+    /// Sticker colours of a face as rows, laid out as on an unfolded net with U above F.
+    pub fn face_colors(&self, face: Face) -> Vec<Vec<Face>> {
+        let outer = self.size as i32 - 1;
+        // Directions across and down the face as it appears on the net
+        let (right, down) = match face {
+            Face::U => ([1, 0, 0], [0, 0, 1]),
+            Face::D => ([1, 0, 0], [0, 0, -1]),
+            Face::F => ([1, 0, 0], [0, -1, 0]),
+            Face::B => ([-1, 0, 0], [0, -1, 0]),
+            Face::R => ([0, 0, -1], [0, -1, 0]),
+            Face::L => ([0, 0, 1], [0, -1, 0]),
+        };
+        let normal = face.normal();
+
+        (0..self.size as i32)
+            .map(|row| {
+                (0..self.size as i32)
+                    .map(|col| {
+                        let pos: [i32; 3] = std::array::from_fn(|i| {
+                            normal[i] * outer
+                                + right[i] * (2 * col - outer)
+                                + down[i] * (2 * row - outer)
+                        });
+                        self.color_at(pos, face).unwrap_or(face)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// Positive quarter turn about the given axis
+fn rotate(v: [i32; 3], axis: usize) -> [i32; 3] {
+    let [x, y, z] = v;
+    match axis {
+        0 => [x, -z, y],
+        1 => [z, y, -x],
+        _ => [-y, x, z],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scrambled(size: usize, scramble: &str) -> CubeState {
+        let mut state = CubeState::solved(size);
+        state.apply_all(&parse_moves(scramble, size).unwrap());
+        state
+    }
+
+    // Colours of every face as letters, one row per string, in U R F D L B order
+    fn faces(state: &CubeState) -> Vec<Vec<String>> {
+        Face::ALL
+            .iter()
+            .map(|&face| {
+                state
+                    .face_colors(face)
+                    .iter()
+                    .map(|row| row.iter().map(|color| color.as_char()).collect())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn rows(rows: &[&str]) -> Vec<String> {
+        rows.iter().map(|row| row.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_wca_notation() {
+        let turn = |face, depth, amount| Move::Turn {
+            face,
+            depth,
+            amount,
+        };
+        assert_eq!(Move::parse("R"), Some(turn(Face::R, 1, 1)));
+        assert_eq!(Move::parse("U'"), Some(turn(Face::U, 1, 3)));
+        assert_eq!(Move::parse("F2"), Some(turn(Face::F, 1, 2)));
+        assert_eq!(Move::parse("B2'"), Some(turn(Face::B, 1, 2)));
+        assert_eq!(Move::parse("Rw"), Some(turn(Face::R, 2, 1)));
+        assert_eq!(Move::parse("r'"), Some(turn(Face::R, 2, 3)));
+        assert_eq!(Move::parse("3Rw"), Some(turn(Face::R, 3, 1)));
+        assert_eq!(Move::parse("3Lw2"), Some(turn(Face::L, 3, 2)));
+        assert_eq!(
+            Move::parse("M'"),
+            Some(Move::Slice {
+                face: Face::L,
+                amount: 3
+            })
+        );
+        assert_eq!(
+            Move::parse("y2"),
+            Some(Move::Rotation {
+                face: Face::U,
+                amount: 2
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_moves() {
+        for token in [
+            "", "R3", "R''", "'R", "2R", "1Rw", "03Rw", "Rw3", "rw", "3r", "X", "m", "G", "RU",
+        ] {
+            assert_eq!(Move::parse(token), None, "{token}");
+        }
+    }
+
+    #[test]
+    fn notation_round_trips() {
+        for token in [
+            "R", "U'", "F2", "Rw", "3Bw'", "M2", "E", "S'", "x", "y'", "z2",
+        ] {
+            assert_eq!(Move::parse(token).unwrap().to_string(), token);
+        }
+    }
+
+    #[test]
+    fn rejects_moves_too_big_for_the_cube() {
+        assert!(parse_moves("R U Rw", 2).is_err());
+        assert!(parse_moves("M", 2).is_err());
+        assert!(parse_moves("3Rw", 3).is_err());
+        assert!(parse_moves("3Rw", 4).is_ok());
+        assert!(parse_moves("3Fw' x y2", 7).is_ok());
+    }
+
+    #[test]
+    fn reports_the_position_of_a_bad_token() {
+        assert_eq!(
+            parse_moves("R U  Q2 F", 3),
+            Err(ParseError {
+                token: "Q2".to_string(),
+                position: 5
+            })
+        );
+        assert_eq!(parse_moves("  ", 3), Ok(vec![]));
+    }
+
+    #[test]
+    fn single_turns() {
+        let state = scrambled(3, "R");
+        assert_eq!(
+            faces(&state),
+            vec![
+                rows(&["UUF", "UUF", "UUF"]),
+                rows(&["RRR", "RRR", "RRR"]),
+                rows(&["FFD", "FFD", "FFD"]),
+                rows(&["DDB", "DDB", "DDB"]),
+                rows(&["LLL", "LLL", "LLL"]),
+                rows(&["UBB", "UBB", "UBB"]),
+            ]
+        );
+
+        let state = scrambled(4, "Uw'");
+        assert_eq!(faces(&state)[2], rows(&["LLLL", "LLLL", "FFFF", "FFFF"]));
+
+        let state = scrambled(7, "3Rw");
+        assert_eq!(faces(&state)[0], rows(&["UUUUFFF"; 7]));
+
+        let state = scrambled(5, "S");
+        assert_eq!(
+            faces(&state)[0],
+            rows(&["UUUUU", "LLLLL", "LLLLL", "LLLLL", "UUUUU"])
+        );
+    }
+
+    #[test]
+    fn rotations_move_the_whole_cube() {
+        for size in 2..=7 {
+            let state = scrambled(size, "x");
+            assert!(state.is_solved());
+            assert_eq!(
+                state.face_colors(Face::U),
+                CubeState::solved(size).face_colors(Face::F)
+            );
+        }
+        assert_eq!(faces(&scrambled(3, "x")), faces(&scrambled(3, "Rw L'")));
+        assert_eq!(faces(&scrambled(3, "y")), faces(&scrambled(3, "U E' D'")));
+        assert_eq!(faces(&scrambled(3, "z")), faces(&scrambled(3, "F S B'")));
+        assert_eq!(faces(&scrambled(4, "x")), faces(&scrambled(4, "3Rw L'")));
+    }
+
+    #[test]
+    fn checkerboards() {
+        let state = scrambled(3, "R2 L2 U2 D2 F2 B2");
+        assert_eq!(faces(&state)[0], rows(&["UDU", "DUD", "UDU"]));
+        assert_eq!(faces(&state)[2], rows(&["FBF", "BFB", "FBF"]));
+        assert_eq!(faces(&scrambled(3, "M2 E2 S2")), faces(&state));
+
+        // The same turns only rotate the cube when they cover half of it
+        assert!(scrambled(2, "R2 L2 U2 D2 F2 B2").is_solved());
+        assert!(scrambled(4, "Rw2 Lw2 Uw2 Dw2 Fw2 Bw2").is_solved());
+        assert!(scrambled(6, "3Rw2 3Lw2 3Uw2 3Dw2 3Fw2 3Bw2").is_solved());
+    }
+
+    #[test]
+    fn real_scrambles() {
+        // Random-move scrambles in WCA notation, one per size, with every face as it should
+        // come out in the U R F D L B order of `faces`
+        type Case = (usize, &'static [&'static str], [&'static [&'static str]; 6]);
+        let cases: [Case; 6] = [
+            (
+                2,
+                &["U F2 U F' U2 R2 F' U' F2 R' F2"],
+                [
+                    &["BR", "FR"],
+                    &["BF", "FR"],
+                    &["LD", "UL"],
+                    &["LD", "DU"],
+                    &["RU", "LB"],
+                    &["DU", "FB"],
+                ],
+            ),
+            (
+                3,
+                &["U2 L2 B2 L U' R' D' L' D2 L B U2 B2 R2 F D L' F' D' B"],
+                [
+                    &["BBU", "LUU", "DRF"],
+                    &["RRF", "DRR", "LBL"],
+                    &["BDD", "DFF", "FLU"],
+                    &["DUB", "RDD", "RLF"],
+                    &["RBL", "FLL", "DBL"],
+                    &["RUU", "FBU", "UFB"],
+                ],
+            ),
+            (
+                4,
+                &[
+                    "B' L Lw2 Fw' Bw Rw' U2 Bw2 Rw Lw F' U2 Bw2 B L2 B2 L' F R2 F2 Lw Rw L B2",
+                    "L' Lw' Uw2 D' Fw' F Lw' L' D U Fw2 R2 Dw2 Rw' Fw2 B",
+                ],
+                [
+                    &["DBDD", "BFRD", "DLUD", "RLFB"],
+                    &["URBR", "RDLL", "BLFU", "RBRF"],
+                    &["BULL", "RBLB", "UFRD", "LULB"],
+                    &["UFBU", "FBRR", "DDBF", "FLDL"],
+                    &["BULD", "DFUF", "LDUR", "RFUF"],
+                    &["FFUL", "FBRL", "RUDU", "DRBU"],
+                ],
+            ),
+            (
+                5,
+                &[
+                    "F' R Rw' Uw Dw2 U R' D2 B Bw U L' Rw' F R Dw' F2 Lw' Fw' B' Dw' F U' Dw'",
+                    "B' Uw2 Lw' F2 Uw2 F Dw' Lw2 U' Fw2 R Dw Rw Bw2 F' L2 Dw D Fw Bw2 B F U2",
+                    "D Uw' Bw2 L2 Uw Fw L2 D L' R' Fw D' Bw'",
+                ],
+                [
+                    &["FDRFR", "URRFF", "FBUDL", "LDLUL", "LBDBD"],
+                    &["FFURB", "LDFFU", "BURRD", "BRBBD", "LDFBU"],
+                    &["UDBRR", "URDFD", "RFFRR", "UBLRU", "BDURB"],
+                    &["DLBDD", "FDDLB", "BUDBD", "LURBL", "LFDRF"],
+                    &["DFUUF", "LBLDR", "FBLLU", "RLUFB", "UBLUR"],
+                    &["ULFRL", "RUDLU", "LFBFL", "FLUUB", "RFRDB"],
+                ],
+            ),
+            (
+                6,
+                &[
+                    "Uw' 3Bw' 3Fw Bw 3Dw' Bw2 Dw 3Rw2 Fw Lw' 3Uw 3Lw D' 3Bw B2 Dw 3Fw2 B2",
+                    "3Bw' Bw' 3Uw B2 3Bw' 3Uw' Uw 3Lw2 Fw' U Fw' L' 3Dw L' Bw' Rw B U' Uw Rw2",
+                    "Dw 3Bw2 Fw2 Bw L2 Bw' Dw' B Bw2 3Bw U' 3Bw' Rw' 3Fw' Bw2 3Dw' 3Fw' R",
+                    "3Fw2 D L U L2 U Rw' 3Rw' F Uw 3Fw2 Dw2 L2 Lw' F' Lw' R U2 Rw B R Bw B",
+                    "Dw2",
+                ],
+                [
+                    &["LFRLBR", "DBFULR", "RRUUDB", "DULFFF", "LUBFFF", "DLULBF"],
+                    &["UDUUBF", "UFUDLL", "LDDDRL", "BUFRFL", "DBRBBD", "BDDRFU"],
+                    &["RUFUUR", "RULBFR", "DULFUD", "LFLDUR", "RFLFDR", "UDBFDD"],
+                    &["LBDDBL", "FRLRRF", "DBBBLB", "UBRLLU", "LBFDLR", "RRFFBL"],
+                    &["FLDFFB", "LRLLDB", "RDURRR", "UBFBUB", "DLDDRF", "BBBLUB"],
+                    &["DUUBUD", "UUDRDF", "FLBDRF", "BRURFR", "RUBBDL", "FLLRUU"],
+                ],
+            ),
+            (
+                7,
+                &[
+                    "F2 3Dw' Fw2 3Bw Lw2 L' 3Rw2 3Dw Bw' B' Dw2 B2 3Uw' F' 3Rw2 U' D2 L2 Lw",
+                    "3Fw' 3Dw2 Fw2 Dw' Fw2 F 3Lw' 3Rw' 3Bw' Rw2 3Lw2 U 3Fw2 Fw2 Dw2 B' 3Bw'",
+                    "U2 3Lw' Uw' F 3Dw2 Dw' 3Fw' R 3Rw2 F2 Bw2 3Lw2 L Uw Fw' Bw2 D' Lw' 3Rw2",
+                    "R' U2 L F2 L R' 3Rw F2 3Fw' Lw Rw2 Uw' R2 3Fw' Uw' F' L' 3Bw 3Lw2 3Uw D'",
+                    "L' 3Bw L U2 3Fw2 Lw' 3Bw' L2 Rw Bw' F2 3Rw Lw' 3Dw 3Bw' 3Rw 3Dw' Uw' R",
+                    "3Uw2 Fw 3Bw2 3Rw2 Bw'",
+                ],
+                [
+                    &[
+                        "FRFBFFL", "RDUDLDB", "DRBFUFB", "RFLUBDF", "LRRBDDD", "LBLFBRD", "UFBURUB",
+                    ],
+                    &[
+                        "DFRDRUB", "FFLRFBB", "LFULRDR", "LFDRFLU", "UDBRRBF", "BLDBBFL", "BRDLBDR",
+                    ],
+                    &[
+                        "FRDBUBR", "BLFDRUL", "BFBBFRD", "DLRFDBU", "FFLULBB", "FDDULUR", "UFLFDBL",
+                    ],
+                    &[
+                        "BDURRLU", "DFLDDRD", "LRFDFBB", "RLLDFUF", "LLUBDUU", "UFLRDUB", "DLRDBRU",
+                    ],
+                    &[
+                        "RFFDUBL", "UUDBUBR", "BUDUDUR", "UBRLUUB", "LBURLLR", "LRUUFLL", "FRDBFLR",
+                    ],
+                    &[
+                        "DUDLUDD", "DBURRDF", "FBFFBUL", "RLUBDRF", "URRLLFF", "URBFRLU", "FULLUDL",
+                    ],
+                ],
+            ),
+        ];
+        for (size, scramble, expected) in cases {
+            let state = scrambled(size, &scramble.join(" "));
+            let expected: Vec<Vec<String>> = expected.iter().map(|face| rows(face)).collect();
+            assert_eq!(faces(&state), expected, "{size}x{size}");
+        }
+    }
+
+    #[test]
+    fn inverse_solves_the_scramble() {
+        let scramble = "R U' 3Fw2 M y Lw' 3Dw B2' x' S E2 D' 2Rw";
+        for size in 4..=7 {
+            let moves = parse_moves(scramble, size).unwrap();
+            let mut state = CubeState::solved(size);
+            state.apply_all(&moves);
+            assert!(!state.is_solved());
+            state.apply_all(&invert(&moves));
+            assert!(state.is_solved());
+        }
+        assert!(parse_moves(scramble, 3).is_err());
+    }
+
+    #[test]
+    fn sexy_move_has_order_six() {
+        for size in 2..=7 {
+            let mut state = CubeState::solved(size);
+            let moves = parse_moves("R U R' U'", size).unwrap();
+            for i in 1..=6 {
+                state.apply_all(&moves);
+                assert_eq!(state.is_solved(), i == 6);
+            }
+        }
+    }
+}
@@ -0,0 +1,364 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! A D-Bus service for controlling the timer from other programs, such as foot pedals, stream
+//! overlays and scripts.
+//!
+//! The service owns `uk.co.cappsy.Tesseract.Timer` on the session bus and serves the
+//! `uk.co.cappsy.Tesseract.Timer` interface at `/uk/co/cappsy/Tesseract/Timer`. For example:
+//!
+//! ```sh
+//! busctl --user call uk.co.cappsy.Tesseract.Timer /uk/co/cappsy/Tesseract/Timer \
+//!     uk.co.cappsy.Tesseract.Timer AddSolve s "12.34"
+//! ```
+
+use crate::record::Cube;
+use crate::timer::{self, Entry};
+use cosmic::iced::futures::{SinkExt, Stream, channel::mpsc};
+use cosmic::iced::stream;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use zbus::fdo;
+use zbus::object_server::{InterfaceRef, SignalEmitter};
+
+pub const NAME: &str = "uk.co.cappsy.Tesseract.Timer";
+pub const PATH: &str = "/uk/co/cappsy/Tesseract/Timer";
+
+/// Something asked of the app over D-Bus.
+#[derive(Debug, Clone)]
+pub enum Request {
+    Start,
+    Stop,
+    Cancel,
+    SwitchPuzzle(Cube),
+    AddSolve(Entry),
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// The service is running, and app state should be reported to it.
+    Connected(Remote),
+    Request(Request),
+}
+
+/// What the app reports to other programs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct State {
+    /// One of "stopped", "inspecting", "holding", "ready" or "running".
+    pub status: String,
+    pub scramble: String,
+    pub puzzle: String,
+    /// Whether the timer can be started and stopped, rather than results being typed in or
+    /// the puzzle being solved some other way.
+    pub timer_enabled: bool,
+    /// Whether results can be added as times.
+    pub entries_enabled: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Signal {
+    /// The state went from the first to the second.
+    StateChanged(State, State),
+    SolveAdded {
+        puzzle: String,
+        result: String,
+        scramble: String,
+    },
+}
+
+/// The app's side of the service, used to keep its state current and emit signals.
+#[derive(Debug, Clone)]
+pub struct Remote {
+    state: Arc<Mutex<State>>,
+    signals: UnboundedSender<Signal>,
+}
+impl Remote {
+    pub fn update(&self, state: State) {
+        let Ok(mut current) = self.state.lock() else {
+            return;
+        };
+        if *current != state {
+            let old = std::mem::replace(&mut *current, state.clone());
+            let _ = self.signals.send(Signal::StateChanged(old, state));
+        }
+    }
+    pub fn solve_added(&self, puzzle: String, result: String, scramble: String) {
+        let _ = self.signals.send(Signal::SolveAdded {
+            puzzle,
+            result,
+            scramble,
+        });
+    }
+}
+
+struct Timer {
+    state: Arc<Mutex<State>>,
+    events: mpsc::Sender<Event>,
+}
+impl Timer {
+    fn state(&self) -> State {
+        self.state
+            .lock()
+            .map(|state| state.clone())
+            .unwrap_or_default()
+    }
+    async fn request(&self, request: Request) -> fdo::Result<()> {
+        self.events
+            .clone()
+            .send(Event::Request(request))
+            .await
+            .map_err(|err| fdo::Error::Failed(err.to_string()))
+    }
+    fn require_timer(&self) -> fdo::Result<()> {
+        match self.state().timer_enabled {
+            true => Ok(()),
+            false => Err(fdo::Error::Failed(
+                "The timer is not in use for this puzzle or mode".to_string(),
+            )),
+        }
+    }
+}
+
+#[zbus::interface(name = "uk.co.cappsy.Tesseract.Timer")]
+impl Timer {
+    /// Start timing a solve, as when releasing the space bar.
+    async fn start(&self) -> fdo::Result<()> {
+        self.require_timer()?;
+        self.request(Request::Start).await
+    }
+
+    /// Stop the timer and record the solve.
+    async fn stop(&self) -> fdo::Result<()> {
+        self.require_timer()?;
+        self.request(Request::Stop).await
+    }
+
+    /// Stop the timer without recording anything.
+    async fn cancel(&self) -> fdo::Result<()> {
+        self.request(Request::Cancel).await
+    }
+
+    /// Switch to a puzzle by name, as listed in `Puzzles`.
+    async fn switch_puzzle(&self, puzzle: &str) -> fdo::Result<()> {
+        let cube = Cube::ALL
+            .into_iter()
+            .find(|cube| cube.as_string() == puzzle)
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("Unknown puzzle \"{puzzle}\"")))?;
+        self.request(Request::SwitchPuzzle(cube)).await
+    }
+
+    /// Record a result for the current scramble, such as "12.34", "1:02.55", "DNF" or "12.34+".
+    async fn add_solve(&self, result: &str) -> fdo::Result<()> {
+        let entry = timer::parse_entry(result)
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("Not a result: \"{result}\"")))?;
+        if !self.state().entries_enabled {
+            return Err(fdo::Error::Failed(
+                "Results can't be added for this puzzle or mode".to_string(),
+            ));
+        }
+        self.request(Request::AddSolve(entry)).await
+    }
+
+    #[zbus(property)]
+    async fn status(&self) -> String {
+        self.state().status
+    }
+
+    #[zbus(property)]
+    async fn scramble(&self) -> String {
+        self.state().scramble
+    }
+
+    #[zbus(property)]
+    async fn puzzle(&self) -> String {
+        self.state().puzzle
+    }
+
+    #[zbus(property)]
+    async fn puzzles(&self) -> Vec<String> {
+        Cube::ALL.iter().map(|cube| cube.as_string()).collect()
+    }
+
+    #[zbus(signal)]
+    async fn state_changed(emitter: &SignalEmitter<'_>, status: &str) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    async fn solve_added(
+        emitter: &SignalEmitter<'_>,
+        puzzle: &str,
+        result: &str,
+        scramble: &str,
+    ) -> zbus::Result<()>;
+}
+
+/// Run the service on the session bus, yielding requests for the app for as long as it runs.
+pub fn serve() -> impl Stream<Item = Event> {
+    stream::channel(100, |mut events: mpsc::Sender<Event>| async move {
+        let state = Arc::new(Mutex::new(State::default()));
+        let (signals, pending) = unbounded_channel();
+        let timer = Timer {
+            state: state.clone(),
+            events: events.clone(),
+        };
+
+        let connection = match connect(timer).await {
+            Ok(connection) => connection,
+            Err(err) => {
+                tracing::error!("Failed to start D-Bus service: {err}");
+                return;
+            }
+        };
+        let _ = events
+            .send(Event::Connected(Remote { state, signals }))
+            .await;
+
+        emit_signals(&connection, pending).await;
+    })
+}
+
+/// Emit the signals the app sends for as long as it keeps the service.
+async fn emit_signals(connection: &zbus::Connection, mut pending: UnboundedReceiver<Signal>) {
+    let Ok(timer) = connection.object_server().interface::<_, Timer>(PATH).await else {
+        return;
+    };
+    while let Some(signal) = pending.recv().await {
+        let result = match signal {
+            Signal::StateChanged(old, new) => state_changed(&timer, &old, &new).await,
+            Signal::SolveAdded {
+                puzzle,
+                result,
+                scramble,
+            } => Timer::solve_added(timer.signal_emitter(), &puzzle, &result, &scramble).await,
+        };
+        if let Err(err) = result {
+            tracing::warn!("Failed to emit D-Bus signal: {err}");
+        }
+    }
+}
+
+/// Signal `StateChanged` and `PropertiesChanged` for whatever differs between two states, so
+/// clients that cache properties see the new values.
+async fn state_changed(timer: &InterfaceRef<Timer>, old: &State, new: &State) -> zbus::Result<()> {
+    let emitter = timer.signal_emitter();
+    let interface = timer.get().await;
+    if old.status != new.status {
+        Timer::state_changed(emitter, &new.status).await?;
+        interface.status_changed(emitter).await?;
+    }
+    if old.scramble != new.scramble {
+        interface.scramble_changed(emitter).await?;
+    }
+    if old.puzzle != new.puzzle {
+        interface.puzzle_changed(emitter).await?;
+    }
+    Ok(())
+}
+
+async fn connect(timer: Timer) -> zbus::Result<zbus::Connection> {
+    zbus::connection::Builder::session()?
+        .name(NAME)?
+        .serve_at(PATH, timer)?
+        .build()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmic::iced::futures::StreamExt;
+
+    #[zbus::proxy(
+        interface = "uk.co.cappsy.Tesseract.Timer",
+        default_path = "/uk/co/cappsy/Tesseract/Timer"
+    )]
+    trait TimerInterface {
+        fn add_solve(&self, result: &str) -> zbus::Result<()>;
+        fn switch_puzzle(&self, puzzle: &str) -> zbus::Result<()>;
+        fn start(&self) -> zbus::Result<()>;
+        #[zbus(property)]
+        fn scramble(&self) -> zbus::Result<String>;
+    }
+
+    // Needs a session bus, such as one started with `dbus-run-session cargo test`
+    #[tokio::test]
+    async fn requests_reach_the_app() {
+        if std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_none() {
+            eprintln!("No session bus, skipping");
+            return;
+        }
+        let (events, mut requests) = mpsc::channel(10);
+        let state = Arc::new(Mutex::new(State {
+            scramble: "R U R' U'".to_string(),
+            entries_enabled: true,
+            ..State::default()
+        }));
+        let timer = Timer {
+            state: state.clone(),
+            events,
+        };
+        let (signals, pending) = unbounded_channel();
+        let remote = Remote {
+            state: state.clone(),
+            signals,
+        };
+        // A private connection without the well-known name, so a running app is unaffected
+        let server = zbus::connection::Builder::session()
+            .unwrap()
+            .serve_at(PATH, timer)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        tokio::spawn({
+            let server = server.clone();
+            async move { emit_signals(&server, pending).await }
+        });
+        let client = zbus::Connection::session().await.unwrap();
+        let server_name = server.unique_name().unwrap().to_string();
+        let proxy = TimerInterfaceProxy::builder(&client)
+            .destination(server_name.as_str())
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(proxy.scramble().await.unwrap(), "R U R' U'");
+
+        // The proxy caches properties, so it only sees a new scramble if the change is signalled
+        let mut changes = proxy.receive_scramble_changed().await;
+        let current = state.lock().unwrap().clone();
+        remote.update(State {
+            scramble: "F2 D' L".to_string(),
+            ..current
+        });
+        let changed = async {
+            while let Some(change) = changes.next().await {
+                if change.get().await.unwrap() == "F2 D' L" {
+                    break;
+                }
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(5), changed)
+            .await
+            .unwrap();
+        assert_eq!(proxy.scramble().await.unwrap(), "F2 D' L");
+
+        proxy.add_solve("12.34+").await.unwrap();
+        assert!(matches!(
+            requests.next().await,
+            Some(Event::Request(Request::AddSolve(Entry::PlusTwo(12_340))))
+        ));
+        assert!(proxy.add_solve("twelve").await.is_err());
+
+        proxy.switch_puzzle("4x4x4").await.unwrap();
+        assert!(matches!(
+            requests.next().await,
+            Some(Event::Request(Request::SwitchPuzzle(Cube::Four)))
+        ));
+        assert!(proxy.switch_puzzle("Megaminx").await.is_err());
+
+        // Typed results and the like have no timer to start
+        assert!(proxy.start().await.is_err());
+        state.lock().unwrap().entries_enabled = false;
+        assert!(proxy.add_solve("12.34").await.is_err());
+    }
+}
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Fewest moves attempts: a one hour countdown and a typed, validated solution.
+
+use crate::cube::{CubeState, Move};
+use std::time::{Duration, Instant};
+
+pub const TIME_LIMIT: Duration = Duration::from_secs(60 * 60);
+pub const MOVE_LIMIT: u32 = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Warning {
+    None,
+    FiveMinutes,
+    OneMinute,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolutionError {
+    Empty,
+    InvalidMove(String),
+    TooLong(u32),
+    NotSolved,
+}
+
+#[derive(Debug)]
+pub struct Attempt {
+    started: Instant,
+    pub solution: String,
+    pub error: Option<SolutionError>,
+}
+impl Attempt {
+    pub fn new() -> Attempt {
+        Attempt {
+            started: Instant::now(),
+            solution: String::new(),
+            error: None,
+        }
+    }
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed().min(TIME_LIMIT)
+    }
+    pub fn remaining(&self) -> Duration {
+        TIME_LIMIT - self.elapsed()
+    }
+    pub fn expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+    pub fn warning(&self) -> Warning {
+        let remaining = self.remaining();
+        if remaining <= Duration::from_secs(60) {
+            Warning::OneMinute
+        } else if remaining <= Duration::from_secs(5 * 60) {
+            Warning::FiveMinutes
+        } else {
+            Warning::None
+        }
+    }
+    pub fn display(&self) -> String {
+        let seconds = self.remaining().as_secs();
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// Check that `solution` solves `scramble` and return its length in HTM.
+///
+/// Rotations are free and outer block turns (e.g. `Rw`) count as one move, as in the WCA
+/// regulations. Slice moves are not permitted and are rejected as invalid.
+pub fn check_solution(scramble: &[String], solution: &str) -> Result<u32, SolutionError> {
+    let moves = solution
+        .split_whitespace()
+        .map(|token| match Move::parse(token).filter(|mv| mv.fits(3)) {
+            Some(Move::Slice { .. }) | None => Err(SolutionError::InvalidMove(token.into())),
+            Some(mv) => Ok(mv),
+        })
+        .collect::<Result<Vec<Move>, SolutionError>>()?;
+
+    if moves.is_empty() {
+        return Err(SolutionError::Empty);
+    }
+
+    let count = moves
+        .iter()
+        .filter(|mv| matches!(mv, Move::Turn { .. }))
+        .count() as u32;
+    if count > MOVE_LIMIT {
+        return Err(SolutionError::TooLong(count));
+    }
+
+    let mut state = CubeState::solved(3);
+    for token in scramble {
+        match Move::parse(token).filter(|mv| mv.fits(3)) {
+            Some(mv) => state.apply(&mv),
+            None => return Err(SolutionError::InvalidMove(token.clone())),
+        }
+    }
+    state.apply_all(&moves);
+
+    if state.is_solved() {
+        Ok(count)
+    } else {
+        Err(SolutionError::NotSolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scramble(moves: &str) -> Vec<String> {
+        moves.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn counts_the_moves_of_a_solution() {
+        assert_eq!(check_solution(&scramble("R U F2"), "F2 U' R'"), Ok(3));
+        // Outer block turns count as one move
+        assert_eq!(check_solution(&scramble("Rw U"), "U' Rw'"), Ok(2));
+    }
+
+    #[test]
+    fn rotations_are_free() {
+        assert_eq!(check_solution(&scramble("R U"), "U' y F' y'"), Ok(2));
+        assert_eq!(check_solution(&scramble("F"), "x U' x'"), Ok(1));
+    }
+
+    #[test]
+    fn rejects_solutions_that_dont_solve() {
+        assert_eq!(
+            check_solution(&scramble("R U F2"), "F2 U' R"),
+            Err(SolutionError::NotSolved)
+        );
+        assert_eq!(
+            check_solution(&scramble("R"), "  "),
+            Err(SolutionError::Empty)
+        );
+        let long = vec!["R R'"; 41].join(" ");
+        assert_eq!(
+            check_solution(&scramble(""), &long),
+            Err(SolutionError::TooLong(82))
+        );
+    }
+
+    #[test]
+    fn rejects_slice_moves() {
+        assert_eq!(
+            check_solution(&scramble("M2"), "M2"),
+            Err(SolutionError::InvalidMove("M2".into()))
+        );
+        assert_eq!(
+            check_solution(&scramble("R"), "R' E"),
+            Err(SolutionError::InvalidMove("E".into()))
+        );
+        assert_eq!(
+            check_solution(&scramble("R"), "R' Q"),
+            Err(SolutionError::InvalidMove("Q".into()))
+        );
+    }
+}
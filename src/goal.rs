@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Goals set for a puzzle, such as a sub-15 AO12 by December or 100 solves this week, and how
+//! far the record has come towards them.
+
+use crate::record::{Cube, Record, Solve};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Kind {
+    Single,
+    Ao5,
+    Ao12,
+    Ao100,
+    Solves,
+}
+impl Kind {
+    pub const ALL: [Kind; 5] = [
+        Kind::Single,
+        Kind::Ao5,
+        Kind::Ao12,
+        Kind::Ao100,
+        Kind::Solves,
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Goal {
+    pub cube: Cube,
+    pub kind: Kind,
+    /// Result to get under, as stored in solves, or the number of solves to do.
+    pub target: u32,
+    /// When the goal was set, as a Unix timestamp. Only solves from then on count towards a
+    /// single or a number of solves, while averages are the current ones.
+    pub created: u64,
+    /// End of the last day for the goal, as a Unix timestamp.
+    pub deadline: Option<u64>,
+    pub achieved: Option<u64>,
+}
+
+/// Where a goal stands, with `fraction` from 0 to 1 for a progress bar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    pub current: Option<u32>,
+    pub fraction: f32,
+}
+
+impl Goal {
+    // Solves since the goal was set, newest first, as records keep them
+    fn solves_since<'a>(&self, record: &'a Record) -> impl Iterator<Item = &'a Solve> {
+        let created = self.created;
+        record
+            .solves
+            .iter()
+            .take_while(move |solve| solve.timestamp.is_some_and(|time| time >= created))
+    }
+
+    pub fn progress(&self, record: &Record) -> Progress {
+        let current = match self.kind {
+            Kind::Single => self
+                .solves_since(record)
+                .filter(|solve| !solve.dnf)
+                .map(Solve::value)
+                .min(),
+            Kind::Ao5 => record.ao5,
+            Kind::Ao12 => record.ao12,
+            Kind::Ao100 => record.ao100,
+            Kind::Solves => {
+                let deadline = self.deadline.unwrap_or(u64::MAX);
+                Some(
+                    self.solves_since(record)
+                        .filter(|solve| solve.timestamp.is_some_and(|time| time <= deadline))
+                        .count() as u32,
+                )
+            }
+        };
+        let fraction = match (self.kind, current) {
+            (_, None) => 0.0,
+            (Kind::Solves, Some(count)) => count as f32 / self.target.max(1) as f32,
+            // How much of the way down to the target time, as far as a bar can show it
+            (_, Some(value)) => self.target as f32 / value.max(1) as f32,
+        };
+        Progress {
+            current,
+            fraction: fraction.min(1.0),
+        }
+    }
+
+    pub fn is_met(&self, record: &Record) -> bool {
+        match (self.kind, self.progress(record).current) {
+            (_, None) => false,
+            (Kind::Solves, Some(count)) => count >= self.target,
+            (_, Some(value)) => value < self.target,
+        }
+    }
+
+    pub fn is_missed(&self, now: u64) -> bool {
+        self.achieved.is_none() && self.deadline.is_some_and(|deadline| deadline < now)
+    }
+
+    /// Mark the goal achieved at `now` if the record meets it in time, returning whether it
+    /// was only just achieved.
+    pub fn check(&mut self, record: &Record, now: u64) -> bool {
+        if self.achieved.is_some() || self.is_missed(now) || !self.is_met(record) {
+            return false;
+        }
+        self.achieved = Some(now);
+        true
+    }
+}
+
+/// Read a date such as "2026-12-01" as the end of that day in the local time zone.
+pub fn parse_deadline(text: &str) -> Option<u64> {
+    let date: jiff::civil::Date = text.trim().parse().ok()?;
+    let end = date
+        .tomorrow()
+        .ok()?
+        .to_zoned(jiff::tz::TimeZone::system())
+        .ok()?;
+    Some(end.timestamp().as_second() as u64 - 1)
+}
+
+/// Format a deadline as the date it falls on in the local time zone.
+pub fn deadline_date(deadline: u64) -> String {
+    jiff::Timestamp::from_second(deadline as i64)
+        .map(|time| {
+            time.to_zoned(jiff::tz::TimeZone::system())
+                .strftime("%Y-%m-%d")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::solve;
+
+    fn goal(kind: Kind, target: u32) -> Goal {
+        Goal {
+            cube: Cube::Three,
+            kind,
+            target,
+            created: 100,
+            deadline: Some(200),
+            achieved: None,
+        }
+    }
+
+    #[test]
+    fn only_solves_since_the_goal_count() {
+        let mut record = Record::default();
+        record.add_solve(solve(9_000, 50));
+        record.add_solve(solve(16_000, 150));
+        let mut single = goal(Kind::Single, 15_000);
+        assert_eq!(single.progress(&record).current, Some(16_000));
+        assert!(!single.check(&record, 150));
+
+        record.add_solve(solve(14_990, 160));
+        assert!(single.check(&record, 160));
+        assert_eq!(single.achieved, Some(160));
+        // Already achieved
+        assert!(!single.check(&record, 170));
+    }
+
+    #[test]
+    fn solve_counts_stop_at_the_deadline() {
+        let mut record = Record::default();
+        for timestamp in [120, 150, 250] {
+            record.add_solve(solve(10_000, timestamp));
+        }
+        let mut count = goal(Kind::Solves, 3);
+        let progress = count.progress(&record);
+        assert_eq!(progress.current, Some(2));
+        assert!((progress.fraction - 2.0 / 3.0).abs() < 1e-6);
+        assert!(count.is_missed(250));
+        assert!(!count.check(&record, 250));
+    }
+
+    #[test]
+    fn averages_use_the_record() {
+        let mut record = Record::default();
+        for timestamp in 101..106 {
+            record.add_solve(solve(12_000, timestamp));
+        }
+        let mut ao5 = goal(Kind::Ao5, 15_000);
+        assert_eq!(ao5.progress(&record).fraction, 1.0);
+        assert!(ao5.check(&record, 110));
+    }
+}
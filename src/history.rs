@@ -0,0 +1,262 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Undo and redo of changes to solving records.
+
+use crate::record::{Record, Solve};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Oldest edits are forgotten beyond this
+const LIMIT: usize = 50;
+
+/// The solves an edit changed in the record stored under `key`, so only those are kept rather
+/// than the whole record.
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub key: String,
+    /// Solves no longer in the record, with their positions before the edit.
+    removed: Vec<(usize, Solve)>,
+    /// Solves new to the record, with their positions after the edit.
+    added: Vec<(usize, Solve)>,
+    /// Solves still in the record but changed, such as by a penalty or comment, before and
+    /// after.
+    edited: Vec<(Solve, Solve)>,
+}
+impl Change {
+    /// What changed in the record under `key` between `before` and `after`.
+    pub fn between(key: String, before: &Record, after: &Record) -> Change {
+        let by_id = |record: &Record| -> HashMap<Uuid, usize> {
+            record
+                .solves
+                .iter()
+                .enumerate()
+                .map(|(position, solve)| (solve.id, position))
+                .collect()
+        };
+        let (before_ids, after_ids) = (by_id(before), by_id(after));
+
+        let removed = before
+            .solves
+            .iter()
+            .enumerate()
+            .filter(|(_, solve)| !after_ids.contains_key(&solve.id))
+            .map(|(position, solve)| (position, solve.clone()))
+            .collect();
+        let mut added = vec![];
+        let mut edited = vec![];
+        for (position, solve) in after.solves.iter().enumerate() {
+            match before_ids.get(&solve.id) {
+                None => added.push((position, solve.clone())),
+                Some(&old) if before.solves[old] != *solve => {
+                    edited.push((before.solves[old].clone(), solve.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        Change {
+            key,
+            removed,
+            added,
+            edited,
+        }
+    }
+
+    /// Take `record` from after the change back to before it.
+    pub fn undo(&self, record: &mut Record) {
+        apply(
+            record,
+            &self.added,
+            &self.removed,
+            self.edited.iter().map(|(before, _)| before),
+        );
+    }
+
+    /// Make the change again on `record` as it was before it.
+    pub fn redo(&self, record: &mut Record) {
+        apply(
+            record,
+            &self.removed,
+            &self.added,
+            self.edited.iter().map(|(_, after)| after),
+        );
+    }
+}
+
+// Take out the solves in `remove`, put back the solves in `insert` where they were, and
+// replace solves with their versions in `replace`
+fn apply<'a>(
+    record: &mut Record,
+    remove: &[(usize, Solve)],
+    insert: &[(usize, Solve)],
+    replace: impl Iterator<Item = &'a Solve>,
+) {
+    record
+        .solves
+        .retain(|solve| !remove.iter().any(|(_, removed)| removed.id == solve.id));
+    for solve in replace {
+        if let Some(current) = record.solve_mut(solve.id) {
+            *current = solve.clone();
+        }
+    }
+    // In order of position, so each lands where it was with the ones before it in place
+    let mut insert = insert.to_vec();
+    insert.sort_by_key(|(position, _)| *position);
+    for (position, solve) in insert {
+        let position = position.min(record.solves.len());
+        record.solves.insert(position, solve);
+    }
+    record.recalculate();
+}
+
+/// An edit such as adding or removing solves or changing a penalty or comment, which may touch
+/// more than one record and is undone as a whole.
+#[derive(Debug, Clone)]
+pub struct Edit {
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+impl History {
+    pub fn push(&mut self, edit: Edit) {
+        self.redo.clear();
+        self.undo.push(edit);
+        if self.undo.len() > LIMIT {
+            self.undo.remove(0);
+        }
+    }
+    pub fn undo(&mut self) -> Option<&Edit> {
+        let edit = self.undo.pop()?;
+        self.redo.push(edit);
+        self.redo.last()
+    }
+    pub fn redo(&mut self) -> Option<&Edit> {
+        let edit = self.redo.pop()?;
+        self.undo.push(edit);
+        self.undo.last()
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::{Penalty, solve};
+
+    fn record(times: &[u32]) -> Record {
+        let mut record = Record::default();
+        for (n, &time) in times.iter().enumerate() {
+            record.add_solve(solve(time, n as u64));
+        }
+        record
+    }
+
+    // An edit to `record`, returned with the change it made
+    fn edit(record: &mut Record, edit: impl FnOnce(&mut Record)) -> Change {
+        let before = record.clone();
+        edit(record);
+        record.recalculate();
+        Change::between("record_three".to_string(), &before, record)
+    }
+
+    // Which edit is which, by the time of the solve it added
+    fn edit_added(edit: &Edit) -> u32 {
+        edit.changes[0].added[0].1.time
+    }
+
+    fn added(time: u32) -> Edit {
+        let mut record = Record::default();
+        let change = edit(&mut record, |record| record.add_solve(solve(time, 0)));
+        Edit {
+            changes: vec![change],
+        }
+    }
+
+    #[test]
+    fn undoes_and_redoes_each_kind_of_change() {
+        let original = record(&[10_000, 11_000, 12_000, 13_000, 14_000, 15_000]);
+        let mut record = original.clone();
+        let (first, third) = (record.solves[0].id, record.solves[2].id);
+        let change = edit(&mut record, |record| {
+            record.remove_solve(first);
+            record.solve_mut(third).unwrap().set_penalty(Penalty::Dnf);
+            record.add_solve(solve(9_000, 10));
+        });
+        assert_eq!((change.removed.len(), change.added.len()), (1, 1));
+        assert_eq!(change.edited.len(), 1);
+        let edited = record.clone();
+
+        change.undo(&mut record);
+        assert_eq!(record, original);
+        change.redo(&mut record);
+        assert_eq!(record, edited);
+    }
+
+    #[test]
+    fn puts_removed_solves_back_where_they_were() {
+        let original = record(&[10_000, 11_000, 12_000, 13_000, 14_000]);
+        let mut record = original.clone();
+        let ids = [0, 2, 4].map(|i| record.solves[i].id);
+        let change = edit(&mut record, |record| {
+            record.solves.retain(|solve| !ids.contains(&solve.id));
+        });
+        change.undo(&mut record);
+        assert_eq!(record, original);
+
+        let change = edit(&mut record, |record| record.solves = vec![]);
+        change.undo(&mut record);
+        assert_eq!(record, original);
+    }
+
+    #[test]
+    fn undoes_newest_first_and_redoes_in_order() {
+        let mut history = History::default();
+        assert!(!history.can_undo() && !history.can_redo());
+        for time in [1, 2, 3] {
+            history.push(added(time));
+        }
+        assert_eq!(history.undo().map(edit_added), Some(3));
+        assert_eq!(history.undo().map(edit_added), Some(2));
+        assert!(history.can_redo());
+        assert_eq!(history.redo().map(edit_added), Some(2));
+        assert_eq!(history.undo().map(edit_added), Some(2));
+        assert_eq!(history.undo().map(edit_added), Some(1));
+        assert!(history.undo().is_none());
+        assert_eq!(history.redo().map(edit_added), Some(1));
+    }
+
+    #[test]
+    fn a_new_edit_clears_redo() {
+        let mut history = History::default();
+        history.push(added(1));
+        history.push(added(2));
+        history.undo();
+        history.push(added(3));
+        assert!(!history.can_redo());
+        assert!(history.redo().is_none());
+        assert_eq!(history.undo().map(edit_added), Some(3));
+        assert_eq!(history.undo().map(edit_added), Some(1));
+    }
+
+    #[test]
+    fn forgets_the_oldest_edits_beyond_the_limit() {
+        let mut history = History::default();
+        for time in 0..LIMIT as u32 + 5 {
+            history.push(added(time));
+        }
+        let mut undone = vec![];
+        while let Some(edit) = history.undo() {
+            undone.push(edit_added(edit));
+        }
+        assert_eq!(undone.len(), LIMIT);
+        assert_eq!(undone.last(), Some(&5));
+    }
+}
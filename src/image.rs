@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Sticker diagrams of cube states.
+
+use crate::cube::{CubeState, Face};
+use cosmic::iced::{self, Alignment, Border, Color};
+use cosmic::prelude::*;
+use cosmic::widget::{self, Space, container};
+
+const GAP: u16 = 2;
+
+// Standard colour scheme, white on top and green in front
+fn color(face: Face) -> Color {
+    match face {
+        Face::U => Color::WHITE,
+        Face::R => Color::from_rgb8(0xc4, 0x1e, 0x3a),
+        Face::F => Color::from_rgb8(0x00, 0x9e, 0x60),
+        Face::D => Color::from_rgb8(0xff, 0xd5, 0x00),
+        Face::L => Color::from_rgb8(0xff, 0x58, 0x00),
+        Face::B => Color::from_rgb8(0x00, 0x51, 0xba),
+    }
+}
+
+fn sticker<'a, M: 'a>(face: Face, width: u16, height: u16) -> Element<'a, M> {
+    container("")
+        .width(width)
+        .height(height)
+        .style(move |_| container::Style {
+            background: Some(iced::Background::Color(color(face))),
+            border: Border {
+                radius: 3.into(),
+                width: 1.0,
+                color: Color::BLACK,
+            },
+            ..Default::default()
+        })
+        .into()
+}
+
+fn strip<'a, M: 'a>(faces: &[Face], width: u16, height: u16) -> widget::Row<'a, M> {
+    faces
+        .iter()
+        .fold(widget::row([]).spacing(GAP), |row, &face| {
+            row.push(sticker(face, width, height))
+        })
+}
+
+fn face<'a, M: 'a>(colors: &[Vec<Face>], sticker_size: u16) -> Element<'a, M> {
+    colors
+        .iter()
+        .fold(widget::column([]).spacing(GAP), |column, row| {
+            column.push(strip(row, sticker_size, sticker_size))
+        })
+        .into()
+}
+
+/// Unfolded net of the whole cube, with U above and D below the F face, each face about
+/// `face_size` wide.
+pub fn net<'a, M: 'a>(state: &CubeState, face_size: u16) -> Element<'a, M> {
+    let n = state.size() as u16;
+    let sticker_size = (face_size.saturating_sub(GAP * (n - 1)) / n).max(1);
+    let width = sticker_size * n + GAP * (n - 1);
+    let spacing = GAP * 3;
+    let blank = || Space::new().width(width).height(width);
+
+    widget::column([])
+        .spacing(spacing)
+        .push(
+            widget::row([])
+                .spacing(spacing)
+                .push(blank())
+                .push(face(&state.face_colors(Face::U), sticker_size)),
+        )
+        .push(
+            widget::row([])
+                .spacing(spacing)
+                .push(face(&state.face_colors(Face::L), sticker_size))
+                .push(face(&state.face_colors(Face::F), sticker_size))
+                .push(face(&state.face_colors(Face::R), sticker_size))
+                .push(face(&state.face_colors(Face::B), sticker_size)),
+        )
+        .push(
+            widget::row([])
+                .spacing(spacing)
+                .push(blank())
+                .push(face(&state.face_colors(Face::D), sticker_size)),
+        )
+        .into()
+}
+
+/// Top view of the U face with the top row of each side face around it, as used to
+/// recognise last layer cases.
+pub fn last_layer<'a, M: 'a>(state: &CubeState, size: u16) -> Element<'a, M> {
+    let side = size / 3;
+    let top = state.face_colors(Face::U);
+    let back: Vec<Face> = state.face_colors(Face::B)[0].iter().rev().copied().collect();
+    let front = state.face_colors(Face::F)[0].clone();
+    let right: Vec<Face> = state.face_colors(Face::R)[0].iter().rev().copied().collect();
+    let left = state.face_colors(Face::L)[0].clone();
+
+    let mut column = widget::column([])
+        .spacing(GAP)
+        .align_x(Alignment::Center)
+        .push(strip(&back, size, side));
+    for (i, row) in top.iter().enumerate() {
+        column = column.push(
+            widget::row([])
+                .spacing(GAP)
+                .push(sticker(left[i], side, size))
+                .push(Space::new().width(GAP))
+                .push(strip(row, size, size))
+                .push(Space::new().width(GAP))
+                .push(sticker(right[i], side, size)),
+        );
+    }
+    column.push(strip(&front, size, side)).into()
+}
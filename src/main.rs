@@ -1,9 +1,27 @@
 // SPDX-License-Identifier: GPL-3.0
 
+mod analysis;
 mod app;
+mod backup;
+mod calendar;
+mod cube;
+mod dbus;
+mod fmc;
+mod goal;
+mod history;
 mod i18n;
+mod image;
+mod overlay;
 mod record;
+mod scramble;
+mod share;
+mod sound;
+mod sync;
 mod timer;
+mod trainer;
+mod trigger;
+mod training;
+mod virtual_cube;
 
 fn main() -> cosmic::iced::Result {
     let requested_languages = i18n_embed::DesktopLanguageRequester::requested_languages();
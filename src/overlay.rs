@@ -0,0 +1,133 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! A local HTTP server for stream overlays, such as a browser source in OBS.
+//!
+//! `/` serves a page that keeps itself up to date, `/state.json` the current state and
+//! `/events` the same state as Server-Sent Events whenever it changes.
+
+use serde::Serialize;
+use std::net::{Ipv4Addr, SocketAddr};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+
+pub const DEFAULT_PORT: u16 = 8765;
+
+const PAGE: &str = include_str!("../resources/overlay.html");
+
+/// What the overlay shows, with times already formatted as in the app.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct State {
+    pub puzzle: String,
+    pub scramble: String,
+    /// One of "stopped", "inspecting", "holding", "ready" or "running".
+    pub status: String,
+    /// Time shown on the timer, when it isn't running.
+    pub time: String,
+    /// When the running timer started, in milliseconds since the Unix epoch.
+    pub started: Option<u64>,
+    /// Digits after the decimal point the running time is counted in, as set in the app.
+    pub digits: usize,
+    pub last_solve: Option<String>,
+    pub ao5: Option<String>,
+    pub ao12: Option<String>,
+}
+
+/// Serve the overlay on `port` of the loopback interface, until `state` is dropped.
+pub async fn serve(port: u16, state: watch::Receiver<State>) -> Result<(), String> {
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))
+        .await
+        .map_err(|err| err.to_string())?;
+    let mut closed = state.clone();
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    tokio::spawn(respond(stream, state.clone()));
+                }
+                Err(err) => tracing::warn!("Failed to accept overlay connection: {err}"),
+            },
+            changed = closed.changed() => {
+                if changed.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+async fn respond(stream: TcpStream, mut state: watch::Receiver<State>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    let Ok(Some(request)) = lines.next_line().await else {
+        return;
+    };
+    // Headers are of no interest, but have to be read before replying
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.is_empty() {
+            break;
+        }
+    }
+
+    let path = match request.split_whitespace().collect::<Vec<_>>()[..] {
+        ["GET", path, _] => path.split('?').next().unwrap_or(path).to_string(),
+        _ => {
+            let _ = writer
+                .write_all(&reply("405 Method Not Allowed", "text/plain", ""))
+                .await;
+            return;
+        }
+    };
+
+    match path.as_str() {
+        "/" => {
+            let _ = writer.write_all(&reply("200 OK", "text/html", PAGE)).await;
+        }
+        "/state.json" => {
+            let json = to_json(&state.borrow());
+            let _ = writer
+                .write_all(&reply("200 OK", "application/json", &json))
+                .await;
+        }
+        "/events" => {
+            let head = "HTTP/1.1 200 OK\r\n\
+                Content-Type: text/event-stream\r\n\
+                Cache-Control: no-cache\r\n\
+                Access-Control-Allow-Origin: *\r\n\r\n";
+            if writer.write_all(head.as_bytes()).await.is_err() {
+                return;
+            }
+            loop {
+                let event = format!("data: {}\n\n", to_json(&state.borrow_and_update()));
+                if writer.write_all(event.as_bytes()).await.is_err()
+                    || state.changed().await.is_err()
+                {
+                    return;
+                }
+            }
+        }
+        _ => {
+            let _ = writer
+                .write_all(&reply("404 Not Found", "text/plain", ""))
+                .await;
+        }
+    }
+}
+
+fn reply(status: &str, content_type: &str, body: &str) -> Vec<u8> {
+    format!(
+        "HTTP/1.1 {status}\r\n\
+        Content-Type: {content_type}; charset=utf-8\r\n\
+        Content-Length: {}\r\n\
+        Access-Control-Allow-Origin: *\r\n\
+        Connection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .into_bytes()
+}
+
+fn to_json(state: &State) -> String {
+    serde_json::to_string(state).unwrap_or_default()
+}
@@ -1,7 +1,31 @@
-use crate::timer;
+use crate::timer::{self, Precision};
 use std::time::SystemTime;
 use cosmic::cosmic_config::{self, CosmicConfigEntry, cosmic_config_derive::CosmicConfigEntry};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+/// Result value of a DNF solve or average.
+pub const DNF: u32 = u32::MAX;
+
+/// Schema version of stored records.
+///
+/// 1. Solves with a time, timestamp, scramble and penalty flags; averages ignore penalties.
+/// 2. Solves may also carry a move count, FMC solution, training case and comment; averages
+///    count +2 and DNF penalties.
+/// 3. Solves may carry split times.
+/// 4. Solves may carry an ID and when they were last edited, for syncing between devices.
+/// 5. Every solve has an ID.
+pub const VERSION: u32 = 5;
+
+// Records saved before the schema was versioned have no version field
+fn first_version() -> u32 {
+    1
+}
+
+// Solves from before IDs were required may have none, left nil until the record is migrated
+fn id_or_nil<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Uuid, D::Error> {
+    Ok(Option::<Uuid>::deserialize(deserializer)?.unwrap_or_default())
+}
 
 #[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
 pub enum Cube {
@@ -12,9 +36,20 @@ pub enum Cube {
     Five,
     Six,
     Seven,
+    ThreeFmc,
     // TODO: add all the other WCA events
 }
 impl Cube {
+    pub const ALL: [Cube; 7] = [
+        Cube::Two,
+        Cube::Three,
+        Cube::Four,
+        Cube::Five,
+        Cube::Six,
+        Cube::Seven,
+        Cube::ThreeFmc,
+    ];
+
     pub fn as_string(&self) -> String {
         match self {
             Cube::Two => "2x2x2".to_string(),
@@ -23,6 +58,30 @@ impl Cube {
             Cube::Five => "5x5x5".to_string(),
             Cube::Six => "6x6x6".to_string(),
             Cube::Seven => "7x7x7".to_string(),
+            Cube::ThreeFmc => "3x3x3 FMC".to_string(),
+        }
+    }
+    pub fn size(&self) -> usize {
+        match self {
+            Cube::Two => 2,
+            Cube::Three | Cube::ThreeFmc => 3,
+            Cube::Four => 4,
+            Cube::Five => 5,
+            Cube::Six => 6,
+            Cube::Seven => 7,
+        }
+    }
+    pub fn scramble_type(&self) -> String {
+        match self {
+            Cube::ThreeFmc => Cube::Three.as_string(),
+            _ => self.as_string(),
+        }
+    }
+    pub fn format_result(&self, value: u32, precision: Precision) -> String {
+        match self {
+            _ if value == DNF => "DNF".to_string(),
+            Cube::ThreeFmc => format!("{}.{:02}", value / 100, value % 100),
+            _ => timer::format_from_ms(value, precision),
         }
     }
     pub fn config_key(&self) -> &str {
@@ -33,17 +92,56 @@ impl Cube {
             Cube::Five => "record_five",
             Cube::Six => "record_six",
             Cube::Seven => "record_seven",
+            Cube::ThreeFmc => "record_three_fmc",
         }
     }
 }
 
+/// Format a Unix timestamp as a date and time in the local time zone.
+pub fn local_time(timestamp: u64) -> String {
+    jiff::Timestamp::from_second(timestamp as i64)
+        .map(|time| {
+            time.to_zoned(jiff::tz::TimeZone::system())
+                .strftime("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Penalty {
+    None,
+    PlusTwo,
+    Dnf,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Solve {
     pub time: u32,
     pub timestamp: Option<u64>,
     pub scramble: Vec<String>,
-    pub _dnf: bool,
-    pub _plus_two: bool,
+    #[serde(rename = "_dnf")]
+    pub dnf: bool,
+    #[serde(rename = "_plus_two")]
+    pub plus_two: bool,
+    #[serde(default)]
+    pub moves: Option<u32>,
+    #[serde(default)]
+    pub solution: Option<String>,
+    #[serde(default)]
+    pub case: Option<String>,
+    #[serde(default)]
+    pub comment: Option<String>,
+    /// Time at the end of each phase but the last, for multi-phase timing.
+    #[serde(default)]
+    pub splits: Vec<u32>,
+    /// Identifies the solve in messages, undo history and across devices, as its position in
+    /// the record changes whenever a solve is added or removed.
+    #[serde(default, deserialize_with = "id_or_nil")]
+    pub id: Uuid,
+    /// When the penalty or comment was last changed, as a Unix timestamp.
+    #[serde(default)]
+    pub edited: Option<u64>,
 }
 impl Solve {
     pub fn new(time: u32, scramble: &Vec<String>) -> Solve {
@@ -54,17 +152,76 @@ impl Solve {
                 .unwrap()
                 .as_secs()),
             scramble: scramble.clone(),
-            _dnf: false,
-            _plus_two: false,
+            dnf: false,
+            plus_two: false,
+            moves: None,
+            solution: None,
+            case: None,
+            comment: None,
+            splits: vec![],
+            id: Uuid::new_v4(),
+            edited: None,
         }
     }
-    pub fn time(&self) -> String {
-        timer::format_from_ms(self.time)
+    pub fn time(&self, precision: Precision) -> String {
+        timer::format_from_ms(self.time, precision)
+    }
+    pub fn result(&self, precision: Precision) -> String {
+        match self.moves {
+            _ if self.dnf => "DNF".to_string(),
+            Some(moves) => moves.to_string(),
+            None if self.plus_two => {
+                format!("{}+", timer::format_from_ms(self.value(), precision))
+            }
+            None => self.time(precision),
+        }
+    }
+    pub fn penalty(&self) -> Penalty {
+        match (self.dnf, self.plus_two) {
+            (true, _) => Penalty::Dnf,
+            (false, true) => Penalty::PlusTwo,
+            (false, false) => Penalty::None,
+        }
+    }
+    pub fn set_penalty(&mut self, penalty: Penalty) {
+        self.dnf = penalty == Penalty::Dnf;
+        self.plus_two = penalty == Penalty::PlusTwo;
+        self.touch();
+    }
+    pub fn set_comment(&mut self, comment: Option<String>) {
+        self.comment = comment;
+        self.touch();
     }
+    fn touch(&mut self) {
+        self.edited = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|time| time.as_secs());
+    }
+    // Value used for averaging: milliseconds, or hundredths of a move for FMC
+    pub fn value(&self) -> u32 {
+        match self.moves {
+            _ if self.dnf => DNF,
+            Some(moves) => moves * 100,
+            // A penalty on a very long solve must not turn it into a DNF
+            None if self.plus_two => self.time.saturating_add(2_000).min(DNF - 1),
+            None => self.time,
+        }
+    }
+}
+
+/// A solve timed at the given Unix timestamp, for tests.
+#[cfg(test)]
+pub fn solve(time: u32, timestamp: u64) -> Solve {
+    let mut solve = Solve::new(time, &vec![]);
+    solve.timestamp = Some(timestamp);
+    solve
 }
 
 #[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Record {
+    #[serde(default = "first_version")]
+    pub version: u32,
     pub cube: Cube,
     pub solves: Vec<Solve>,
     pub best_solve: Option<Solve>,
@@ -75,6 +232,7 @@ pub struct Record {
 impl Record {
     pub fn default() -> Record {
         Record {
+            version: VERSION,
             cube: Cube::Three,
             solves: vec![],
             best_solve: None,
@@ -83,34 +241,275 @@ impl Record {
             ao100: None,
         }
     }
+    /// Bring a record saved by an older version of the app up to the current schema, one
+    /// version at a time.
+    pub fn migrate(mut self) -> Record {
+        if self.version < 2 {
+            // New fields all default to empty, only the averages need redoing with penalties
+            self.recalculate();
+            self.version = 2;
+        }
+        if self.version < 3 {
+            self.version = 3;
+        }
+        if self.version < 4 {
+            self.version = 4;
+        }
+        if self.version < 5 {
+            for solve in self.solves.iter_mut().filter(|solve| solve.id.is_nil()) {
+                solve.id = Uuid::new_v4();
+            }
+            self.version = 5;
+        }
+        self
+    }
     pub fn add_solve(&mut self, solve: Solve) {
         self.solves.splice(0..0, vec![solve]);
-
-        // Recalculate averages
+        self.recalculate();
+    }
+    pub fn recalculate(&mut self) {
         self.ao5 = calc_average(&self.solves, 5);
         self.ao12 = calc_average(&self.solves, 12);
         self.ao100 = calc_average(&self.solves, 100);
     }
+    /// Position of the solve with `id`, counting from the newest.
+    pub fn position(&self, id: Uuid) -> Option<usize> {
+        self.solves.iter().position(|solve| solve.id == id)
+    }
+    pub fn solve(&self, id: Uuid) -> Option<&Solve> {
+        self.solves.iter().find(|solve| solve.id == id)
+    }
+    pub fn solve_mut(&mut self, id: Uuid) -> Option<&mut Solve> {
+        self.solves.iter_mut().find(|solve| solve.id == id)
+    }
+    pub fn remove_solve(&mut self, id: Uuid) {
+        self.solves.retain(|solve| solve.id != id);
+    }
+    /// The best result that isn't a DNF.
+    pub fn best(&self) -> Option<u32> {
+        self.solves
+            .iter()
+            .filter(|solve| !solve.dnf)
+            .map(Solve::value)
+            .min()
+    }
+    /// Average of `count` solves, going back in time from the solve at `index`.
+    pub fn average_at(&self, index: usize, count: usize) -> Option<u32> {
+        calc_average(&self.solves[index.min(self.solves.len())..], count)
+    }
+    /// Mean and number of solves for each training case, in order of first appearance.
+    pub fn case_means(&self) -> Vec<(String, u32, usize)> {
+        let mut cases: Vec<(String, u64, usize)> = vec![];
+        for solve in self.solves.iter().filter(|s| !s.dnf) {
+            let Some(case) = &solve.case else { continue };
+            match cases.iter_mut().find(|(name, _, _)| name == case) {
+                Some((_, sum, count)) => {
+                    *sum += solve.value() as u64;
+                    *count += 1;
+                }
+                None => cases.push((case.clone(), solve.value() as u64, 1)),
+            }
+        }
+        cases
+            .into_iter()
+            .map(|(name, sum, count)| (name, (sum / count as u64) as u32, count))
+            .collect()
+    }
 }
 
-fn calc_average(solves: &Vec<Solve>, ao: usize) -> Option<u32> {
+fn calc_average(solves: &[Solve], ao: usize) -> Option<u32> {
     if solves.len() >= ao {
-        let last_n: &[Solve] = &solves[0..ao];
-        let mut sum = 0;
-        let mut high = 0;
-        let mut low = 0;
-        for solve in last_n {
-            if solve.time > high {
-                high = solve.time;
-            }
-            if solve.time < low || low == 0 {
-                low = solve.time;
-            }
-            sum += solve.time;
+        let mut values: Vec<u32> = solves[0..ao].iter().map(|s| s.value()).collect();
+        values.sort();
+
+        // Only the single worst result is dropped, so a second DNF makes the average a DNF
+        let counted = &values[1..ao - 1];
+        if counted.contains(&DNF) {
+            return Some(DNF);
         }
-        sum = sum - high - low;
-        Some(sum / (ao as u32 - 2))
+        let sum: u64 = counted.iter().map(|&v| v as u64).sum();
+        Some((sum / (ao as u64 - 2)) as u32)
     } else {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plus_two_never_makes_a_dnf() {
+        let mut solve = Solve::new(12_000, &vec![]);
+        solve.plus_two = true;
+        assert_eq!(solve.value(), 14_000);
+
+        solve.time = DNF - 1_000;
+        assert_eq!(solve.value(), DNF - 1);
+        solve.time = DNF - 1;
+        assert_eq!(solve.value(), DNF - 1);
+    }
+
+    // Records as cosmic-config stored them, in RON, before each schema change
+    const VERSION_1: &str = r#"(
+        cube: Three,
+        solves: [
+            (time: 9000, timestamp: Some(500), scramble: ["R", "U"], _dnf: false, _plus_two: false),
+            (time: 13000, timestamp: Some(400), scramble: ["F"], _dnf: false, _plus_two: false),
+            (time: 12000, timestamp: Some(300), scramble: ["L2"], _dnf: false, _plus_two: true),
+            (time: 11000, timestamp: None, scramble: [], _dnf: false, _plus_two: false),
+            (time: 10000, timestamp: Some(100), scramble: ["D'"], _dnf: true, _plus_two: false),
+        ],
+        best_solve: Some(
+            (time: 9000, timestamp: Some(500), scramble: ["R", "U"], _dnf: false, _plus_two: false),
+        ),
+        ao5: Some(11000),
+        ao12: None,
+        ao100: None,
+    )"#;
+
+    const VERSION_2: &str = r#"(
+        version: 2,
+        cube: ThreeFmc,
+        solves: [
+            (
+                time: 3600000,
+                timestamp: Some(200),
+                scramble: ["R'", "U'", "F"],
+                _dnf: false,
+                _plus_two: false,
+                moves: Some(3),
+                solution: Some("F' U R"),
+                case: Some("Aa"),
+                comment: Some("lucky"),
+            ),
+        ],
+        best_solve: None,
+        ao5: None,
+        ao12: None,
+        ao100: None,
+    )"#;
+
+    const VERSION_3: &str = r#"(
+        version: 3,
+        cube: Four,
+        solves: [
+            (
+                time: 75000,
+                timestamp: Some(300),
+                scramble: ["Rw", "U2"],
+                _dnf: false,
+                _plus_two: true,
+                moves: None,
+                solution: None,
+                case: None,
+                comment: Some("centres were slow"),
+                splits: [20000, 45000],
+            ),
+        ],
+        best_solve: None,
+        ao5: None,
+        ao12: None,
+        ao100: None,
+    )"#;
+
+    // One solve with an empty ID and one from before the field existed
+    const VERSION_4: &str = r#"(
+        version: 4,
+        cube: Three,
+        solves: [
+            (
+                time: 12000,
+                timestamp: Some(200),
+                scramble: ["R"],
+                _dnf: false,
+                _plus_two: false,
+                moves: None,
+                solution: None,
+                case: None,
+                comment: None,
+                splits: [],
+                id: None,
+            ),
+            (
+                time: 12000,
+                timestamp: Some(100),
+                scramble: ["R"],
+                _dnf: false,
+                _plus_two: false,
+                moves: None,
+                solution: None,
+                case: None,
+                comment: None,
+                splits: [],
+            ),
+        ],
+        best_solve: None,
+        ao5: None,
+        ao12: None,
+        ao100: None,
+    )"#;
+
+    fn migrated(ron: &str, version: u32) -> Record {
+        let record: Record = ron::from_str(ron).unwrap();
+        assert_eq!(record.version, version);
+        let record = record.migrate();
+        assert_eq!(record.version, VERSION);
+        assert!(record.solves.iter().all(|solve| !solve.id.is_nil()));
+        record
+    }
+
+    #[test]
+    fn migrates_unversioned_records() {
+        let record = migrated(VERSION_1, 1);
+        assert_eq!(record.cube, Cube::Three);
+        let times: Vec<u32> = record.solves.iter().map(|solve| solve.time).collect();
+        assert_eq!(times, [9000, 13000, 12000, 11000, 10000]);
+        let timestamps: Vec<Option<u64>> = record.solves.iter().map(|s| s.timestamp).collect();
+        assert_eq!(timestamps, [Some(500), Some(400), Some(300), None, Some(100)]);
+        assert_eq!(record.solves[0].scramble, ["R", "U"]);
+        assert!(record.solves[2].plus_two && !record.solves[2].dnf);
+        assert!(record.solves[4].dnf && !record.solves[4].plus_two);
+        assert!(record.solves.iter().all(|solve| solve.comment.is_none()));
+        assert_eq!(record.best_solve.map(|solve| solve.time), Some(9000));
+        // Averages are redone counting penalties, the +2 making 14.00 and the DNF the worst
+        assert_eq!(record.ao5, Some(12666));
+    }
+
+    #[test]
+    fn migrates_records_with_fmc_and_training_fields() {
+        let record = migrated(VERSION_2, 2);
+        assert_eq!(record.cube, Cube::ThreeFmc);
+        let solve = &record.solves[0];
+        assert_eq!(solve.time, 3_600_000);
+        assert_eq!(solve.timestamp, Some(200));
+        assert_eq!(solve.scramble, ["R'", "U'", "F"]);
+        assert_eq!(solve.moves, Some(3));
+        assert_eq!(solve.solution.as_deref(), Some("F' U R"));
+        assert_eq!(solve.case.as_deref(), Some("Aa"));
+        assert_eq!(solve.comment.as_deref(), Some("lucky"));
+        assert!(solve.splits.is_empty());
+    }
+
+    #[test]
+    fn migrates_records_with_splits() {
+        let record = migrated(VERSION_3, 3);
+        assert_eq!(record.cube, Cube::Four);
+        let solve = &record.solves[0];
+        assert_eq!(solve.time, 75_000);
+        assert!(solve.plus_two);
+        assert_eq!(solve.comment.as_deref(), Some("centres were slow"));
+        assert_eq!(solve.splits, [20_000, 45_000]);
+        assert_eq!(solve.edited, None);
+    }
+
+    #[test]
+    fn solves_without_ids_are_given_them() {
+        let record: Record = ron::from_str(VERSION_4).unwrap();
+        assert!(record.solves.iter().all(|solve| solve.id.is_nil()));
+
+        let record = migrated(VERSION_4, 4);
+        assert_ne!(record.solves[0].id, record.solves[1].id);
+        assert_eq!(record.position(record.solves[1].id), Some(1));
+    }
+}
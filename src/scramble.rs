@@ -0,0 +1,178 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Scrambles typed or pasted in by hand, such as a list from a competition, and random ones
+//! generated ahead of time in the background.
+
+use crate::cube::{self, ParseError};
+use crate::training::{self, Subset};
+use cube_scrambler::generate_scramble;
+use std::collections::{HashMap, VecDeque};
+
+// Random scrambles kept ready for each puzzle
+const QUEUE_LENGTH: usize = 3;
+
+/// A move that couldn't be read, on a 1-based line of the pasted text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineError {
+    pub line: usize,
+    pub error: ParseError,
+}
+
+/// Read one scramble per line for a cube of `size`, skipping blank lines and any numbering
+/// such as "1." or "2)" in front of a scramble. Moves are rewritten in standard notation.
+pub fn parse_list(text: &str, size: usize) -> Result<Vec<Vec<String>>, LineError> {
+    let mut scrambles = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let moves = numbering_end(line);
+        let parsed = cube::parse_moves(&line[moves..], size).map_err(|mut error| {
+            // Positions count from the start of the line, numbering included
+            error.position += line[..moves].chars().count();
+            LineError { line: i + 1, error }
+        })?;
+        if !parsed.is_empty() {
+            scrambles.push(parsed.iter().map(|mv| mv.to_string()).collect());
+        }
+    }
+    Ok(scrambles)
+}
+
+// Byte offset just past a leading number followed by "." or ")", if there is one
+fn numbering_end(line: &str) -> usize {
+    let trimmed = line.trim_start();
+    let digits = trimmed.len()
+        - trimmed
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    match trimmed[digits..].chars().next() {
+        Some('.' | ')') if digits > 0 => line.len() - trimmed.len() + digits + 1,
+        _ => 0,
+    }
+}
+
+/// A generated scramble, with the training case it sets up, if any.
+pub type Generated = (Vec<String>, Option<String>);
+
+/// Random scrambles generated ahead of time, by scramble type, so the next one is ready as
+/// soon as a solve ends. Training scrambles are kept by subset and selected cases instead, each
+/// with the case it sets up.
+#[derive(Debug, Default)]
+pub struct Queue {
+    ready: HashMap<String, VecDeque<Generated>>,
+    // Scrambles still being generated
+    pending: HashMap<String, usize>,
+}
+impl Queue {
+    pub fn next(&mut self, scramble_type: &str) -> Option<Generated> {
+        self.ready.get_mut(scramble_type)?.pop_front()
+    }
+    pub fn peek(&self, scramble_type: &str) -> Option<&Vec<String>> {
+        self.ready
+            .get(scramble_type)?
+            .front()
+            .map(|(scramble, _)| scramble)
+    }
+    pub fn add(&mut self, scramble_type: String, scramble: Vec<String>, case: Option<String>) {
+        if let Some(pending) = self.pending.get_mut(&scramble_type) {
+            *pending = pending.saturating_sub(1);
+        }
+        if !scramble.is_empty() {
+            self.ready
+                .entry(scramble_type)
+                .or_default()
+                .push_back((scramble, case));
+        }
+    }
+    /// Number of scrambles to start generating to fill the queue, which then count as pending.
+    pub fn request(&mut self, scramble_type: &str) -> usize {
+        let ready = self.ready.get(scramble_type).map_or(0, VecDeque::len);
+        let pending = self.pending.entry(scramble_type.to_string()).or_default();
+        let wanted = QUEUE_LENGTH.saturating_sub(ready + *pending);
+        *pending += wanted;
+        wanted
+    }
+}
+
+/// Generate a random scramble on a blocking thread, returned with its scramble type.
+pub async fn generate(scramble_type: String) -> (String, Vec<String>, Option<String>) {
+    let kind = scramble_type.clone();
+    let scramble = tokio::task::spawn_blocking(move || {
+        generate_scramble(None, Some(kind)).unwrap_or_default()
+    })
+    .await
+    .unwrap_or_default();
+    (scramble_type, scramble, None)
+}
+
+/// Generate a scramble for one of the `selected` training cases on a blocking thread, as cross
+/// cases take a search to set up, returned with `key` and the case.
+pub async fn generate_training(
+    key: String,
+    subset: Subset,
+    selected: Vec<String>,
+) -> (String, Vec<String>, Option<String>) {
+    let (case, scramble) =
+        tokio::task::spawn_blocking(move || training::generate(subset, &selected).unzip())
+            .await
+            .unwrap_or_default();
+    (key, scramble.unwrap_or_default(), case)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_one_scramble_per_line() {
+        let text = "R U R' U'\n\n  F2 D B'  \r\nL2\n";
+        assert_eq!(
+            parse_list(text, 3),
+            Ok(vec![
+                vec!["R".to_string(), "U".into(), "R'".into(), "U'".into()],
+                vec!["F2".into(), "D".into(), "B'".into()],
+                vec!["L2".into()],
+            ])
+        );
+        assert_eq!(parse_list("", 3), Ok(vec![]));
+    }
+
+    #[test]
+    fn strips_numbering() {
+        let scrambles = parse_list("1. R U\n2) F'\n  10.D2", 3).unwrap();
+        assert_eq!(scrambles, [vec!["R", "U"], vec!["F'"], vec!["D2"]]);
+        // A number without "." or ")" after it isn't numbering
+        assert_eq!(parse_list("R\n11 B", 3).unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn rewrites_moves_in_standard_notation() {
+        assert_eq!(
+            parse_list("r B2' 3Rw", 4),
+            Ok(vec![vec!["Rw".to_string(), "B2".into(), "3Rw".into()]])
+        );
+    }
+
+    #[test]
+    fn reports_the_line_and_position_of_a_bad_token() {
+        assert_eq!(
+            parse_list("R U\n2. R Q F", 3),
+            Err(LineError {
+                line: 2,
+                error: ParseError {
+                    token: "Q".to_string(),
+                    position: 5
+                }
+            })
+        );
+        // Moves have to fit the puzzle
+        assert_eq!(
+            parse_list("1) R U\n\n3) Rw", 2),
+            Err(LineError {
+                line: 3,
+                error: ParseError {
+                    token: "Rw".to_string(),
+                    position: 3
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,192 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Averages formatted as text for posting elsewhere, such as a team chat.
+
+use crate::record::{self, Cube, Record};
+use crate::timer::Precision;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Template {
+    #[default]
+    Plain,
+    Markdown,
+}
+impl Template {
+    pub const ALL: [Template; 2] = [Template::Plain, Template::Markdown];
+}
+
+/// Text for the average of `count` solves going back in time from the solve at `index`, with
+/// the best and worst results in parentheses and the solves listed oldest first.
+pub fn average_text(
+    record: &Record,
+    cube: &Cube,
+    index: usize,
+    count: usize,
+    template: Template,
+    precision: Precision,
+) -> Option<String> {
+    let average = record.average_at(index, count)?;
+    let solves = &record.solves[index..index + count];
+
+    // The single best and worst results don't count, even when tied with another
+    let best = (0..count).min_by_key(|&i| solves[i].value())?;
+    let worst = (0..count)
+        .rev()
+        .filter(|&i| i != best)
+        .max_by_key(|&i| solves[i].value())?;
+
+    let average = cube.format_result(average, precision);
+    let date = solves[0].timestamp.map(record::local_time);
+    let mut lines = vec![];
+    match template {
+        Template::Plain => {
+            lines.push(format!("{} AO{count}: {average}", cube.as_string()));
+            if let Some(date) = date {
+                lines.push(date);
+            }
+        }
+        Template::Markdown => {
+            let title = format!("**{} AO{count}: {average}**", cube.as_string());
+            lines.push(match date {
+                Some(date) => format!("{title} ({date})"),
+                None => title,
+            });
+        }
+    }
+    lines.push(String::new());
+
+    for (n, i) in (0..count).rev().enumerate() {
+        let solve = &solves[i];
+        let mut result = solve.result(precision);
+        if i == best || i == worst {
+            result = format!("({result})");
+        }
+        let scramble = solve.scramble.join(" ");
+        lines.push(match template {
+            Template::Plain => format!("{}. {result}   {scramble}", n + 1),
+            Template::Markdown => format!("{}. {result} `{scramble}`", n + 1),
+        });
+    }
+
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::{Penalty, solve};
+
+    // A record of solves added oldest first, each scrambled with a single move named after it
+    fn record(times: &[u32]) -> Record {
+        let mut record = Record::default();
+        for (n, &time) in times.iter().enumerate() {
+            let mut solve = solve(time, 1_000 + n as u64);
+            solve.scramble = vec![format!("R{n}")];
+            record.add_solve(solve);
+        }
+        record
+    }
+
+    fn text(record: &Record, count: usize, template: Template) -> Option<String> {
+        average_text(
+            record,
+            &Cube::Three,
+            0,
+            count,
+            template,
+            Precision::Hundredths,
+        )
+    }
+
+    #[test]
+    fn lists_the_solves_oldest_first_with_best_and_worst_in_parentheses() {
+        let record = record(&[13_000, 11_000, 9_000, 12_000, 10_000]);
+        let date = record::local_time(1_004);
+        assert_eq!(
+            text(&record, 5, Template::Plain).unwrap(),
+            format!(
+                "3x3x3 AO5: 11.00\n{date}\n\n\
+                 1. (13.00)   R0\n\
+                 2. 11.00   R1\n\
+                 3. (9.00)   R2\n\
+                 4. 12.00   R3\n\
+                 5. 10.00   R4"
+            )
+        );
+        assert_eq!(
+            text(&record, 5, Template::Markdown).unwrap(),
+            format!(
+                "**3x3x3 AO5: 11.00** ({date})\n\n\
+                 1. (13.00) `R0`\n\
+                 2. 11.00 `R1`\n\
+                 3. (9.00) `R2`\n\
+                 4. 12.00 `R3`\n\
+                 5. 10.00 `R4`"
+            )
+        );
+    }
+
+    #[test]
+    fn leaves_out_the_date_when_the_solves_have_none() {
+        let mut record = record(&[13_000, 11_000, 9_000, 12_000, 10_000]);
+        record.solves[0].timestamp = None;
+        let plain = text(&record, 5, Template::Plain).unwrap();
+        assert!(plain.starts_with("3x3x3 AO5: 11.00\n\n1. "), "{plain}");
+        let markdown = text(&record, 5, Template::Markdown).unwrap();
+        assert!(
+            markdown.starts_with("**3x3x3 AO5: 11.00**\n\n1. "),
+            "{markdown}"
+        );
+    }
+
+    #[test]
+    fn marks_only_one_of_tied_results() {
+        let record = record(&[15_000, 15_000, 12_000, 10_000, 10_000]);
+        let text = text(&record, 5, Template::Plain).unwrap();
+        assert_eq!(text.matches("(15.00)").count(), 1, "{text}");
+        assert_eq!(text.matches("(10.00)").count(), 1, "{text}");
+        assert!(text.starts_with("3x3x3 AO5: 12.33"), "{text}");
+    }
+
+    #[test]
+    fn counts_a_dnf_as_the_worst_result() {
+        let mut record = record(&[9_000, 11_000, 12_000, 13_000, 10_000]);
+        record.solves[2].set_penalty(Penalty::Dnf);
+        let text = text(&record, 5, Template::Plain).unwrap();
+        assert!(text.starts_with("3x3x3 AO5: 11.33"), "{text}");
+        assert!(text.contains("3. (DNF)   R2"), "{text}");
+        assert!(text.contains("1. (9.00)   R0"), "{text}");
+        assert!(text.contains("4. 13.00   R3"), "{text}");
+    }
+
+    #[test]
+    fn shows_a_dnf_average_with_one_dnf_dropped() {
+        let mut record = record(&[9_000, 11_000, 12_000, 13_000, 10_000]);
+        record.solves[1].set_penalty(Penalty::Dnf);
+        record.solves[3].set_penalty(Penalty::Dnf);
+        let text = text(&record, 5, Template::Plain).unwrap();
+        assert!(text.starts_with("3x3x3 AO5: DNF"), "{text}");
+        assert_eq!(text.matches("(DNF)").count(), 1, "{text}");
+        assert_eq!(text.matches("DNF").count(), 3, "{text}");
+    }
+
+    #[test]
+    fn needs_enough_solves() {
+        let record = record(&[13_000, 11_000, 9_000, 12_000]);
+        assert_eq!(text(&record, 5, Template::Plain), None);
+        assert!(text(&record, 3, Template::Plain).is_some());
+        // Counting back from a later solve
+        assert_eq!(
+            average_text(
+                &record,
+                &Cube::Three,
+                2,
+                3,
+                Template::Plain,
+                Precision::Hundredths
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Sound cues, synthesised as short runs of tones, and spoken results.
+
+use rodio::source::{SineWave, Source};
+use rodio::{OutputStream, Sink};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    /// Inspection has reached 8 or 12 seconds.
+    Inspection,
+    /// The timer can be started.
+    Ready,
+    /// The solve just recorded is the best so far.
+    PersonalBest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Beeps,
+    Chimes,
+}
+impl Theme {
+    pub const ALL: [Theme; 2] = [Theme::Beeps, Theme::Chimes];
+
+    // Frequency in Hz and length in milliseconds of each note, where a frequency of 0 is a rest
+    fn notes(&self, cue: Cue) -> &'static [(f32, u64)] {
+        match (self, cue) {
+            (Theme::Beeps, Cue::Inspection) => &[(1175.0, 150)],
+            (Theme::Beeps, Cue::Ready) => &[(1760.0, 80)],
+            (Theme::Beeps, Cue::PersonalBest) => &[
+                (1047.0, 100),
+                (0.0, 30),
+                (1319.0, 100),
+                (0.0, 30),
+                (1568.0, 100),
+                (0.0, 30),
+                (2093.0, 300),
+            ],
+            (Theme::Chimes, Cue::Inspection) => &[(587.0, 300)],
+            (Theme::Chimes, Cue::Ready) => &[(880.0, 250)],
+            (Theme::Chimes, Cue::PersonalBest) => &[
+                (523.0, 180),
+                (659.0, 180),
+                (784.0, 180),
+                (1047.0, 180),
+                (784.0, 120),
+                (1047.0, 600),
+            ],
+        }
+    }
+    // Chimes swell into each note rather than starting sharply
+    fn attack(&self) -> Duration {
+        match self {
+            Theme::Beeps => Duration::ZERO,
+            Theme::Chimes => Duration::from_millis(40),
+        }
+    }
+}
+
+/// Play a cue at `volume`, from 0 to 1, without waiting for it to finish.
+pub fn play(cue: Cue, theme: Theme, volume: f32) {
+    // The output stream can't leave the thread it was opened on, so each cue gets its own
+    std::thread::spawn(move || {
+        let (_stream, handle) = match OutputStream::try_default() {
+            Ok(output) => output,
+            Err(err) => {
+                tracing::warn!("Failed to open audio output: {err}");
+                return;
+            }
+        };
+        let Ok(sink) = Sink::try_new(&handle) else {
+            return;
+        };
+        sink.set_volume(volume);
+        for &(frequency, length) in theme.notes(cue) {
+            let length = Duration::from_millis(length);
+            let note = SineWave::new(frequency)
+                .take_duration(length)
+                .fade_in(theme.attack())
+                .amplify(if frequency > 0.0 { 0.5 } else { 0.0 });
+            sink.append(note);
+        }
+        sink.sleep_until_end();
+    });
+}
+
+/// Whether results can be read aloud, through a running speech-dispatcher or `spd-say`.
+pub fn can_speak() -> bool {
+    speech_socket().is_some()
+        || std::env::var_os("PATH").is_some_and(|paths| {
+            std::env::split_paths(&paths).any(|dir| dir.join("spd-say").is_file())
+        })
+}
+
+/// Read `text` aloud through speech-dispatcher.
+pub fn speak(text: &str) {
+    let text = text.to_string();
+    // Waited on in the background, so speaking never blocks the app
+    std::thread::spawn(move || {
+        // In the Flatpak there is no `spd-say`, only the socket of the host's speech-dispatcher
+        let result = match speech_socket() {
+            Some(socket) => speak_through(&socket, &text),
+            None => Command::new("spd-say").arg(&text).status().map(|_| ()),
+        };
+        if let Err(err) = result {
+            tracing::warn!("Failed to speak: {err}");
+        }
+    });
+}
+
+// The socket speech-dispatcher listens on, if it's running
+fn speech_socket() -> Option<PathBuf> {
+    let socket = match std::env::var("SPEECHD_ADDRESS") {
+        Ok(address) => PathBuf::from(address.strip_prefix("unix_socket:")?),
+        Err(_) => PathBuf::from(std::env::var_os("XDG_RUNTIME_DIR")?)
+            .join("speech-dispatcher")
+            .join("speechd.sock"),
+    };
+    socket.exists().then_some(socket)
+}
+
+// Send `text` to speech-dispatcher in its SSIP protocol, as `spd-say` would
+fn speak_through(socket: &Path, text: &str) -> io::Result<()> {
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut replies = BufReader::new(stream.try_clone()?);
+    let mut send = |command: &str| -> io::Result<()> {
+        stream.write_all(format!("{command}\r\n").as_bytes())?;
+        // A reply can run over several lines, the last with a space after its code
+        loop {
+            let mut reply = String::new();
+            if replies.read_line(&mut reply)? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            if reply.as_bytes().get(3) != Some(&b'-') {
+                return match reply.starts_with('2') {
+                    true => Ok(()),
+                    false => Err(io::Error::other(reply.trim_end().to_string())),
+                };
+            }
+        }
+    };
+    send("SET SELF CLIENT_NAME user:tesseract:main")?;
+    send("SPEAK")?;
+    // The message ends at a line holding only a dot, so dots starting a line are doubled
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| match line.starts_with('.') {
+            true => format!(".{line}"),
+            false => line.to_string(),
+        })
+        .collect();
+    send(&format!("{}\r\n.", lines.join("\r\n")))?;
+    send("QUIT")
+}
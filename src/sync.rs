@@ -0,0 +1,555 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Syncing solving records between devices through shared storage, such as a folder kept in
+//! sync by another program or a WebDAV server.
+//!
+//! Each record is stored as `<config key>.json`, holding every solve and a tombstone for every
+//! solve deleted. Solves are matched by ID, so devices can add solves independently and a sync
+//! is the union of both sides, less anything deleted on either. A solve changed after it was
+//! deleted, including one that came back, such as by undoing its removal, outlives the deletion.
+
+use crate::record::{Record, Solve};
+use ashpd::desktop::ResponseError;
+use ashpd::desktop::file_chooser::SelectedFiles;
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// How often records are synced while syncing is set up.
+pub const INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Somewhere records can be shared from.
+pub trait Backend: Send + Sync {
+    /// What was stored under `name`, or `None` if nothing has been yet.
+    fn load<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>, String>>;
+    fn store<'a>(&'a self, name: &'a str, data: Vec<u8>) -> BoxFuture<'a, Result<(), String>>;
+}
+
+/// Where records are synced to, as set up in settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+    #[default]
+    Off,
+    Folder(PathBuf),
+    WebDav {
+        url: String,
+        username: String,
+        /// Kept in the keyring instead of settings, so only read from settings saved by older
+        /// versions.
+        #[serde(default, skip_serializing)]
+        password: String,
+    },
+}
+impl Target {
+    pub fn backend(&self) -> Option<Arc<dyn Backend>> {
+        match self {
+            Target::Off => None,
+            Target::Folder(path) => Some(Arc::new(Folder { path: path.clone() })),
+            Target::WebDav {
+                url,
+                username,
+                password,
+            } => Some(Arc::new(WebDav {
+                client: reqwest::Client::new(),
+                url: url.trim_end_matches('/').to_string(),
+                username: username.clone(),
+                password: password.clone(),
+            })),
+        }
+    }
+}
+
+/// A solve that was deleted, so other devices delete it too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub id: Uuid,
+    /// When it was deleted, as a Unix timestamp.
+    pub deleted: u64,
+}
+
+/// A record as shared between devices.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shared {
+    /// Newest first, as in a record.
+    pub solves: Vec<Solve>,
+    pub tombstones: Vec<Tombstone>,
+}
+
+/// What a device remembers of a record between syncs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct State {
+    /// Solves the record had after the last sync, so any missing since were deleted here.
+    pub known: Vec<Uuid>,
+    /// Kept here too, so they are shared again if another device overwrote them.
+    pub tombstones: Vec<Tombstone>,
+    /// Whether the record was synced before, as until then solves it has that were deleted
+    /// elsewhere are leftovers rather than brought back.
+    #[serde(default)]
+    pub synced: bool,
+}
+
+pub fn file_name(key: &str) -> String {
+    format!("{key}.json")
+}
+
+/// Merge what's shared into `local`, returning what to share now and whether `local` changed.
+/// `local` has to be migrated first, so every solve has an ID.
+pub fn merge(local: &mut Record, shared: Shared, state: &mut State, now: u64) -> (Shared, bool) {
+    let before = local.solves.clone();
+
+    // Deleted here since the last sync
+    let present: HashSet<Uuid> = local.solves.iter().map(|solve| solve.id).collect();
+    let mut tombstones: HashMap<Uuid, u64> = HashMap::new();
+    let deleted_here = state
+        .known
+        .iter()
+        .filter(|id| !present.contains(id))
+        .map(|&id| Tombstone { id, deleted: now });
+    for tombstone in state
+        .tombstones
+        .iter()
+        .chain(&shared.tombstones)
+        .copied()
+        .chain(deleted_here)
+    {
+        let deleted = tombstones.entry(tombstone.id).or_insert(tombstone.deleted);
+        *deleted = (*deleted).min(tombstone.deleted);
+    }
+
+    // Back here since the last sync despite being deleted, by undoing the removal, restoring a
+    // backup or moving the solve away and back, so changed now to outlive the deletion
+    let known: HashSet<Uuid> = state.known.iter().copied().collect();
+    if state.synced {
+        for solve in local.solves.iter_mut() {
+            if !known.contains(&solve.id) && tombstones.contains_key(&solve.id) {
+                solve.edited = Some(now);
+            }
+        }
+    }
+
+    // Where both sides have a solve, the one edited last wins, and this device's on a tie
+    let mut solves: HashMap<Uuid, Solve> = HashMap::new();
+    for solve in shared.solves.into_iter().chain(local.solves.drain(..)) {
+        match solves.get(&solve.id) {
+            Some(other) if changed_at(other) > changed_at(&solve) => {}
+            _ => {
+                solves.insert(solve.id, solve);
+            }
+        }
+    }
+    // Deletions only stand against solves left as they were
+    tombstones.retain(|id, deleted| {
+        solves
+            .get(id)
+            .is_none_or(|solve| changed_at(solve) <= *deleted)
+    });
+    let mut merged: Vec<Solve> = solves
+        .into_values()
+        .filter(|solve| !tombstones.contains_key(&solve.id))
+        .collect();
+    merged.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then(b.id.cmp(&a.id)));
+
+    let mut tombstones: Vec<Tombstone> = tombstones
+        .into_iter()
+        .map(|(id, deleted)| Tombstone { id, deleted })
+        .collect();
+    tombstones.sort_by_key(|tombstone| (tombstone.deleted, tombstone.id));
+
+    local.solves = merged.clone();
+    local.recalculate();
+    state.known = merged.iter().map(|solve| solve.id).collect();
+    state.tombstones = tombstones.clone();
+    state.synced = true;
+    let changed = local.solves != before;
+    (
+        Shared {
+            solves: merged,
+            tombstones,
+        },
+        changed,
+    )
+}
+
+fn changed_at(solve: &Solve) -> u64 {
+    solve.edited.or(solve.timestamp).unwrap_or_default()
+}
+
+/// A folder shared some other way, such as a network drive or a folder synced by another
+/// program.
+struct Folder {
+    path: PathBuf,
+}
+impl Backend for Folder {
+    fn load<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>, String>> {
+        Box::pin(async move {
+            match tokio::fs::read(self.path.join(name)).await {
+                Ok(data) => Ok(Some(data)),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(err) => Err(err.to_string()),
+            }
+        })
+    }
+    fn store<'a>(&'a self, name: &'a str, data: Vec<u8>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            tokio::fs::create_dir_all(&self.path)
+                .await
+                .map_err(|err| err.to_string())?;
+            // Written whole before replacing the old file, so a reader never sees half of it
+            let partial = self.path.join(format!(".{name}.partial"));
+            tokio::fs::write(&partial, data)
+                .await
+                .map_err(|err| err.to_string())?;
+            tokio::fs::rename(&partial, self.path.join(name))
+                .await
+                .map_err(|err| err.to_string())
+        })
+    }
+}
+
+/// A collection on a WebDAV server, such as a Nextcloud folder.
+struct WebDav {
+    client: reqwest::Client,
+    url: String,
+    username: String,
+    password: String,
+}
+impl WebDav {
+    fn request(&self, method: reqwest::Method, url: String) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match self.username.is_empty() {
+            true => request,
+            false => request.basic_auth(&self.username, Some(&self.password)),
+        }
+    }
+}
+impl Backend for WebDav {
+    fn load<'a>(&'a self, name: &'a str) -> BoxFuture<'a, Result<Option<Vec<u8>>, String>> {
+        Box::pin(async move {
+            let response = self
+                .request(reqwest::Method::GET, format!("{}/{name}", self.url))
+                .send()
+                .await
+                .map_err(|err| err.to_string())?;
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                return Ok(None);
+            }
+            let response = response.error_for_status().map_err(|err| err.to_string())?;
+            let data = response.bytes().await.map_err(|err| err.to_string())?;
+            Ok(Some(data.to_vec()))
+        })
+    }
+    fn store<'a>(&'a self, name: &'a str, data: Vec<u8>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let url = format!("{}/{name}", self.url);
+            let put = || {
+                self.request(reqwest::Method::PUT, url.clone())
+                    .body(data.clone())
+                    .send()
+            };
+            let mut response = put().await.map_err(|err| err.to_string())?;
+            // The collection is made the first time anything is stored in it
+            if response.status() == reqwest::StatusCode::CONFLICT {
+                let mkcol = reqwest::Method::from_bytes(b"MKCOL").map_err(|err| err.to_string())?;
+                self.request(mkcol, format!("{}/", self.url))
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|err| err.to_string())?;
+                response = put().await.map_err(|err| err.to_string())?;
+            }
+            response
+                .error_for_status()
+                .map(|_| ())
+                .map_err(|err| err.to_string())
+        })
+    }
+}
+
+// How the password for a WebDAV account is found in the keyring
+fn password_attributes<'a>(url: &'a str, username: &'a str) -> Vec<(&'static str, &'a str)> {
+    vec![
+        ("application", "tesseract"),
+        ("url", url),
+        ("username", username),
+    ]
+}
+
+/// Keep the password for a WebDAV account in the keyring, through the Secret Service.
+/// Ask the desktop for a folder to sync through, or `None` if none was chosen. Going through
+/// the portal gives the Flatpak access to the folder, as a path that stays valid across runs.
+pub async fn choose_folder(title: String) -> Result<Option<PathBuf>, String> {
+    let request = SelectedFiles::open_file()
+        .title(title.as_str())
+        .directory(true)
+        .modal(true)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    match request.response() {
+        Ok(files) => Ok(files.uris().first().and_then(|uri| uri.to_file_path().ok())),
+        Err(ashpd::Error::Response(ResponseError::Cancelled)) => Ok(None),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+pub async fn store_password(url: String, username: String, password: String) -> Result<(), String> {
+    let keyring = oo7::Keyring::new().await.map_err(|err| err.to_string())?;
+    keyring
+        .create_item(
+            "Tesseract WebDAV password",
+            &password_attributes(&url, &username),
+            password,
+            true,
+        )
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// The password kept in the keyring for a WebDAV account, or an empty one if there is none.
+pub async fn load_password(url: String, username: String) -> Result<String, String> {
+    let keyring = oo7::Keyring::new().await.map_err(|err| err.to_string())?;
+    keyring.unlock().await.map_err(|err| err.to_string())?;
+    let items = keyring
+        .search_items(&password_attributes(&url, &username))
+        .await
+        .map_err(|err| err.to_string())?;
+    let Some(item) = items.first() else {
+        return Ok(String::new());
+    };
+    let secret = item.secret().await.map_err(|err| err.to_string())?;
+    String::from_utf8(secret.to_vec()).map_err(|err| err.to_string())
+}
+
+/// Load what's shared for each record key, failing if any can't be read.
+pub async fn fetch(
+    backend: Arc<dyn Backend>,
+    keys: Vec<String>,
+) -> Result<Vec<(String, Shared)>, String> {
+    let mut shared = vec![];
+    for key in keys {
+        let data = backend.load(&file_name(&key)).await?;
+        let record = match data {
+            Some(data) => serde_json::from_slice(&data)
+                .map_err(|err| format!("{}: {err}", file_name(&key)))?,
+            None => Shared::default(),
+        };
+        shared.push((key, record));
+    }
+    Ok(shared)
+}
+
+/// Store what's shared for each record key.
+pub async fn publish(
+    backend: Arc<dyn Backend>,
+    records: Vec<(String, Shared)>,
+) -> Result<(), String> {
+    for (key, record) in records {
+        let data = serde_json::to_vec(&record).map_err(|err| err.to_string())?;
+        backend.store(&file_name(&key), data).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::solve;
+
+    fn record(solves: Vec<Solve>) -> Record {
+        let mut record = Record::default();
+        record.solves = solves;
+        record
+    }
+
+    #[test]
+    fn solves_from_both_devices_are_kept() {
+        let (a, b) = (solve(10_000, 100), solve(11_000, 200));
+        let mut first = record(vec![a.clone()]);
+        let mut first_state = State::default();
+        let (shared, changed) = merge(&mut first, Shared::default(), &mut first_state, 300);
+        assert!(!changed);
+
+        let mut second = record(vec![b.clone()]);
+        let (shared, changed) = merge(&mut second, shared, &mut State::default(), 300);
+        assert!(changed);
+        assert_eq!(second.solves, vec![b.clone(), a.clone()]);
+
+        merge(&mut first, shared, &mut first_state, 300);
+        assert_eq!(first.solves, second.solves);
+    }
+
+    #[test]
+    fn deletions_spread_and_stay_deleted() {
+        let (a, b) = (solve(10_000, 100), solve(11_000, 200));
+        let mut first = record(vec![b.clone(), a.clone()]);
+        let mut first_state = State::default();
+        let (shared, _) = merge(&mut first, Shared::default(), &mut first_state, 300);
+        let mut second = record(vec![]);
+        let mut second_state = State::default();
+        merge(&mut second, shared.clone(), &mut second_state, 300);
+
+        // Deleted on the first device, then synced while the second still has it
+        first.solves.retain(|solve| solve.id != a.id);
+        let (shared, _) = merge(&mut first, shared, &mut first_state, 400);
+        assert_eq!(
+            shared.tombstones,
+            vec![Tombstone {
+                id: a.id,
+                deleted: 400
+            }]
+        );
+
+        let (_, changed) = merge(&mut second, shared, &mut second_state, 500);
+        assert!(changed);
+        assert_eq!(second.solves, vec![b.clone()]);
+
+        // Even if the shared copy lost the tombstone, the first device shares it again
+        let lost = Shared {
+            solves: vec![b.clone(), a.clone()],
+            tombstones: vec![],
+        };
+        let (shared, _) = merge(&mut first, lost, &mut first_state, 600);
+        assert_eq!(first.solves, vec![b]);
+        assert_eq!(shared.tombstones.len(), 1);
+    }
+
+    #[test]
+    fn undoing_a_removal_after_a_sync_brings_the_solve_back() {
+        let (a, b) = (solve(10_000, 100), solve(11_000, 200));
+        let mut first = record(vec![b.clone(), a.clone()]);
+        let mut first_state = State::default();
+        let (shared, _) = merge(&mut first, Shared::default(), &mut first_state, 300);
+        let mut second = record(vec![]);
+        let mut second_state = State::default();
+        merge(&mut second, shared.clone(), &mut second_state, 300);
+
+        first.solves.retain(|solve| solve.id != a.id);
+        let (shared, _) = merge(&mut first, shared, &mut first_state, 400);
+        merge(&mut second, shared.clone(), &mut second_state, 400);
+        assert_eq!(second.solves, vec![b.clone()]);
+
+        // Undone, after the deletion was shared
+        first.solves.push(a.clone());
+        let (shared, changed) = merge(&mut first, shared, &mut first_state, 500);
+        assert!(changed);
+        assert_eq!(first.solves.len(), 2);
+        assert!(shared.tombstones.is_empty());
+
+        // The second device still remembers the deletion, but the solve is newer
+        let (shared, _) = merge(&mut second, shared, &mut second_state, 600);
+        assert_eq!(second.solves, first.solves);
+        assert!(second_state.tombstones.is_empty());
+        merge(&mut first, shared, &mut first_state, 700);
+        assert_eq!(first.solves.len(), 2);
+    }
+
+    #[test]
+    fn a_solve_moved_away_and_back_stays() {
+        let a = solve(10_000, 100);
+        let mut three = record(vec![a.clone()]);
+        let mut four = record(vec![]);
+        let (mut three_state, mut four_state) = (State::default(), State::default());
+        let (three_shared, _) = merge(&mut three, Shared::default(), &mut three_state, 200);
+        let (four_shared, _) = merge(&mut four, Shared::default(), &mut four_state, 200);
+
+        // Moved to the other record and synced
+        four.solves = std::mem::take(&mut three.solves);
+        let (three_shared, _) = merge(&mut three, three_shared, &mut three_state, 300);
+        let (four_shared, _) = merge(&mut four, four_shared, &mut four_state, 300);
+        assert_eq!(three_shared.tombstones.len(), 1);
+        assert_eq!(four_shared.solves.len(), 1);
+
+        // And back again
+        three.solves = std::mem::take(&mut four.solves);
+        let (three_shared, _) = merge(&mut three, three_shared, &mut three_state, 400);
+        let (four_shared, _) = merge(&mut four, four_shared, &mut four_state, 400);
+        assert_eq!(three.solves.len(), 1);
+        assert_eq!(three.solves[0].id, a.id);
+        assert!(three_shared.tombstones.is_empty());
+        assert!(four.solves.is_empty());
+        assert_eq!(four_shared.tombstones.len(), 1);
+
+        // Another sync leaves it where it is
+        merge(&mut three, three_shared, &mut three_state, 500);
+        merge(&mut four, four_shared, &mut four_state, 500);
+        assert_eq!(three.solves.len(), 1);
+        assert!(four.solves.is_empty());
+    }
+
+    #[test]
+    fn leftovers_are_deleted_on_the_first_sync() {
+        let (a, b) = (solve(10_000, 100), solve(11_000, 200));
+        let shared = Shared {
+            solves: vec![b.clone()],
+            tombstones: vec![Tombstone {
+                id: a.id,
+                deleted: 300,
+            }],
+        };
+        let mut device = record(vec![b.clone(), a]);
+        let (shared, changed) = merge(&mut device, shared, &mut State::default(), 400);
+        assert!(changed);
+        assert_eq!(device.solves, vec![b]);
+        assert_eq!(shared.tombstones.len(), 1);
+    }
+
+    #[test]
+    fn the_last_edit_wins() {
+        let mut a = solve(10_000, 100);
+        let mut first = record(vec![a.clone()]);
+        let mut first_state = State::default();
+        let (shared, _) = merge(&mut first, Shared::default(), &mut first_state, 200);
+
+        a.set_comment(Some("Lucky PLL skip".to_string()));
+        let mut second = record(vec![a.clone()]);
+        let (shared, _) = merge(&mut second, shared, &mut State::default(), 300);
+        merge(&mut first, shared, &mut first_state, 300);
+        assert_eq!(first.solves[0].comment.as_deref(), Some("Lucky PLL skip"));
+    }
+
+    #[tokio::test]
+    async fn folders_store_and_load() {
+        let path = std::env::temp_dir().join(format!("tesseract-sync-{}", Uuid::new_v4()));
+        let backend = Target::Folder(path.clone()).backend().unwrap();
+        assert_eq!(backend.load("record_three.json").await, Ok(None));
+        backend
+            .store("record_three.json", b"{}".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(
+            backend.load("record_three.json").await,
+            Ok(Some(b"{}".to_vec()))
+        );
+        let _ = std::fs::remove_dir_all(path);
+    }
+
+    // Run with a server such as one started by `rclone serve webdav --addr :8080 <dir>` and
+    // `TESSERACT_WEBDAV_URL=http://localhost:8080/tesseract cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs a WebDAV server at TESSERACT_WEBDAV_URL"]
+    async fn webdav_stores_and_loads() {
+        let backend = Target::WebDav {
+            url: std::env::var("TESSERACT_WEBDAV_URL").unwrap(),
+            username: std::env::var("TESSERACT_WEBDAV_USERNAME").unwrap_or_default(),
+            password: std::env::var("TESSERACT_WEBDAV_PASSWORD").unwrap_or_default(),
+        }
+        .backend()
+        .unwrap();
+        let key = Uuid::new_v4().to_string();
+        assert_eq!(backend.load(&file_name(&key)).await, Ok(None));
+
+        let shared = Shared {
+            solves: vec![solve(10_000, 100)],
+            tombstones: vec![],
+        };
+        publish(backend.clone(), vec![(key.clone(), shared.clone())])
+            .await
+            .unwrap();
+        let fetched = fetch(backend, vec![key]).await.unwrap();
+        assert_eq!(fetched[0].1, shared);
+    }
+}
@@ -1,12 +1,70 @@
+use crate::record::Penalty;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Status {
     Stopped,
+    Inspecting,
     Hold,
     Ready,
     Running,
 }
+impl Status {
+    /// Name of the status as reported to other programs.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Stopped => "stopped",
+            Status::Inspecting => "inspecting",
+            Status::Hold => "holding",
+            Status::Ready => "ready",
+            Status::Running => "running",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Precision {
+    Seconds,
+    Tenths,
+    #[default]
+    Hundredths,
+    Thousandths,
+}
+impl Precision {
+    pub const ALL: [Precision; 4] = [
+        Precision::Seconds,
+        Precision::Tenths,
+        Precision::Hundredths,
+        Precision::Thousandths,
+    ];
+
+    pub fn as_string(&self) -> String {
+        match self {
+            Precision::Seconds => "1".to_string(),
+            Precision::Tenths => "0.1".to_string(),
+            Precision::Hundredths => "0.01".to_string(),
+            Precision::Thousandths => "0.001".to_string(),
+        }
+    }
+    /// Digits shown after the decimal point.
+    pub fn digits(&self) -> usize {
+        match self {
+            Precision::Seconds => 0,
+            Precision::Tenths => 1,
+            Precision::Hundredths => 2,
+            Precision::Thousandths => 3,
+        }
+    }
+    // How often a running timer needs redrawing to show every digit change
+    pub fn tick(&self) -> Duration {
+        match self {
+            Precision::Seconds => Duration::from_millis(100),
+            Precision::Tenths => Duration::from_millis(50),
+            Precision::Hundredths | Precision::Thousandths => Duration::from_millis(10),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Timer {
@@ -21,27 +79,263 @@ impl Timer {
             status: Status::Stopped,
         }
     }
-    pub fn display(&self) -> String {
-        let mut displayed_time = format_from_ms(self.time);
-        if self.status == Status::Running {
-            displayed_time.pop();
-        }
-        displayed_time
+    pub fn display(&self, precision: Precision) -> String {
+        format_from_ms(self.time, precision)
     }
     pub fn _start(&mut self) {
         self.status = Status::Running;
     }
 }
 
-pub fn format_from_ms(time: u32) -> String {
-    let duration = Duration::from_millis(time.into());
-    let minutes = duration.as_millis() / 60_000;
-    let seconds = (duration.as_millis() % 60_000) / 1_000;
-    let millis = (duration.as_millis() % 1_000) / 10;
+/// Format a time, truncated (never rounded up) to the given precision.
+pub fn format_from_ms(time: u32, precision: Precision) -> String {
+    let hours = time / 3_600_000;
+    let minutes = time / 60_000 % 60;
+    let seconds = time / 1_000 % 60;
+    let fraction = match precision {
+        Precision::Seconds => String::new(),
+        Precision::Tenths => format!(".{}", time % 1_000 / 100),
+        Precision::Hundredths => format!(".{:02}", time % 1_000 / 10),
+        Precision::Thousandths => format!(".{:03}", time % 1_000),
+    };
 
-    if minutes > 0 {
-        format!("{}:{:02}.{:02}", minutes, seconds, millis)
+    if hours > 0 {
+        format!("{}:{:02}:{:02}{}", hours, minutes, seconds, fraction)
+    } else if minutes > 0 {
+        format!("{}:{:02}{}", minutes, seconds, fraction)
     } else {
-        format!("{}.{:02}", seconds, millis)
+        format!("{}{}", seconds, fraction)
+    }
+}
+
+/// Milliseconds in a duration, saturating below the value reserved for DNF results.
+pub fn ms_from_duration(duration: Duration) -> u32 {
+    u32::try_from(duration.as_millis())
+        .unwrap_or(u32::MAX)
+        .min(u32::MAX - 1)
+}
+
+/// Truncate a time to hundredths of a second, as the WCA regulations do for timer results.
+pub fn wca_round(time: u32) -> u32 {
+    time - time % 10
+}
+
+/// Inspection allowed before a solve by the WCA regulations, in milliseconds.
+pub const INSPECTION: u32 = 15_000;
+/// Inspection after which a solve is a DNF rather than a +2, in milliseconds.
+pub const DNF_INSPECTION: u32 = INSPECTION + 2_000;
+/// Points in inspection at which a cue plays, as a judge calls out "8 seconds" and "12 seconds".
+pub const INSPECTION_CUES: [u32; 2] = [8_000, 12_000];
+
+/// Penalty for a solve started `inspected` milliseconds into inspection: +2 for going over 15
+/// seconds, and DNF for going over 17.
+pub fn inspection_penalty(inspected: u32) -> Penalty {
+    if inspected <= INSPECTION {
+        Penalty::None
+    } else if inspected <= DNF_INSPECTION {
+        Penalty::PlusTwo
+    } else {
+        Penalty::Dnf
+    }
+}
+
+/// Seconds of inspection left, counting down, then the penalty a solve started now would get.
+pub fn format_inspection(inspected: u32) -> String {
+    match inspection_penalty(inspected) {
+        Penalty::None => (INSPECTION - inspected).div_ceil(1_000).to_string(),
+        Penalty::PlusTwo => "+2".to_string(),
+        Penalty::Dnf => "DNF".to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    Time(u32),
+    PlusTwo(u32),
+    Dnf,
+}
+
+/// Parse a typed result such as "12.34", "1:02.55", "1:00:02.5", "DNF" or "12.34+" (a +2
+/// penalty).
+pub fn parse_entry(input: &str) -> Option<Entry> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("dnf") {
+        return Some(Entry::Dnf);
+    }
+
+    let (input, plus_two) = match input.strip_suffix('+') {
+        Some(time) => (time.trim_end(), true),
+        None => (input, false),
+    };
+    // `u32::from_str` also accepts a leading "+", which isn't a valid time
+    let number = |digits: &str| match digits.chars().all(|c| c.is_ascii_digit()) {
+        true => digits.parse::<u32>().ok(),
+        false => None,
+    };
+    let (hours, minutes, seconds) = match input.split(':').collect::<Vec<&str>>()[..] {
+        [seconds] => (0, 0, seconds),
+        [minutes, seconds] => (0, number(minutes)?, seconds),
+        [hours, minutes, seconds] => {
+            let minutes = number(minutes)?;
+            if minutes >= 60 {
+                return None;
+            }
+            (number(hours)?, minutes, seconds)
+        }
+        _ => return None,
+    };
+    let (whole, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    if fraction.len() > 3 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let whole = number(whole)?;
+    if (hours > 0 || minutes > 0) && whole >= 60 {
+        return None;
+    }
+    let millis = format!("{:0<3}", fraction).parse::<u32>().ok()?;
+
+    let time = hours
+        .checked_mul(3_600_000)?
+        .checked_add(minutes.checked_mul(60_000)?)?
+        .checked_add(whole.checked_mul(1_000)?)?
+        .checked_add(millis)
+        // A zero time can't be a real solve, and the largest value is reserved for DNF results
+        .filter(|&time| time > 0 && time < u32::MAX)?;
+    match plus_two {
+        true => Some(Entry::PlusTwo(time)),
+        false => Some(Entry::Time(time)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inspection_counts_down_then_penalises() {
+        let shown =
+            [0, 1, 999, 1_000, 14_999, 15_000, 15_001, 17_000, 17_001].map(format_inspection);
+        assert_eq!(shown, ["15", "15", "15", "14", "1", "0", "+2", "+2", "DNF"]);
+    }
+
+    #[test]
+    fn formats_times_at_every_precision() {
+        let cases = [
+            (
+                Precision::Seconds,
+                ["0", "12", "1:02", "1:00:02", "1193:02:47"],
+            ),
+            (
+                Precision::Tenths,
+                ["0.0", "12.3", "1:02.5", "1:00:02.5", "1193:02:47.2"],
+            ),
+            (
+                Precision::Hundredths,
+                ["0.00", "12.34", "1:02.55", "1:00:02.50", "1193:02:47.29"],
+            ),
+            (
+                Precision::Thousandths,
+                [
+                    "0.000",
+                    "12.345",
+                    "1:02.555",
+                    "1:00:02.500",
+                    "1193:02:47.295",
+                ],
+            ),
+        ];
+        let times = [0, 12_345, 62_555, 3_602_500, u32::MAX];
+        for (precision, expected) in cases {
+            for (time, expected) in times.into_iter().zip(expected) {
+                assert_eq!(
+                    format_from_ms(time, precision),
+                    expected,
+                    "{time} {precision:?}"
+                );
+                // The overlay counts running times in the same number of digits
+                let fraction = expected
+                    .split_once('.')
+                    .map_or("", |(_, fraction)| fraction);
+                assert_eq!(fraction.len(), precision.digits(), "{precision:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn formatting_truncates() {
+        assert_eq!(format_from_ms(12_999, Precision::Hundredths), "12.99");
+        assert_eq!(format_from_ms(59_999, Precision::Seconds), "59");
+        assert_eq!(format_from_ms(3_599_999, Precision::Tenths), "59:59.9");
+        assert_eq!(format_from_ms(3_600_000, Precision::Tenths), "1:00:00.0");
+    }
+
+    #[test]
+    fn durations_saturate_below_dnf() {
+        assert_eq!(ms_from_duration(Duration::from_micros(12_345_678)), 12_345);
+        assert_eq!(
+            ms_from_duration(Duration::from_millis(u32::MAX as u64 - 1)),
+            u32::MAX - 1
+        );
+        assert_eq!(
+            ms_from_duration(Duration::from_millis(u32::MAX as u64)),
+            u32::MAX - 1
+        );
+        assert_eq!(ms_from_duration(Duration::MAX), u32::MAX - 1);
+    }
+
+    #[test]
+    fn wca_rounding_truncates_to_hundredths() {
+        assert_eq!(wca_round(0), 0);
+        assert_eq!(wca_round(12_340), 12_340);
+        assert_eq!(wca_round(12_349), 12_340);
+        assert_eq!(wca_round(u32::MAX - 1), 4_294_967_290);
+    }
+
+    #[test]
+    fn parses_typed_entries() {
+        let cases = [
+            ("12.34", Some(Entry::Time(12_340))),
+            ("  12.34 ", Some(Entry::Time(12_340))),
+            ("12", Some(Entry::Time(12_000))),
+            ("9.5", Some(Entry::Time(9_500))),
+            ("0.123", Some(Entry::Time(123))),
+            ("1:02.55", Some(Entry::Time(62_550))),
+            ("1:00:02.5", Some(Entry::Time(3_602_500))),
+            ("DNF", Some(Entry::Dnf)),
+            ("dnf", Some(Entry::Dnf)),
+            ("12.34+", Some(Entry::PlusTwo(12_340))),
+            ("12.34 +", Some(Entry::PlusTwo(12_340))),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_entry(input), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_entries() {
+        let cases = [
+            "",
+            "+12",
+            "-12",
+            "0",
+            "0.000",
+            "0:00",
+            ".5",
+            "12.3456",
+            "12.3a",
+            "abc",
+            "1:60",
+            "1:60:00",
+            "1::02",
+            "1:2:3:4",
+            "1:+2.5",
+            "+1:02",
+            "12++",
+            "DNF+",
+            "4294967.295",
+        ];
+        for input in cases {
+            assert_eq!(parse_entry(input), None, "{input:?}");
+        }
     }
 }
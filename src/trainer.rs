@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Recognition drills for last layer algorithms, with cases picked by spaced repetition.
+
+use crate::cube::{CubeState, Face, Move};
+use crate::record::{Record, Solve};
+use crate::timer;
+use crate::training::{self, Case, Subset};
+use rand::seq::IndexedRandom;
+use std::time::Instant;
+
+pub const SUBSETS: [Subset; 3] = [Subset::Pll, Subset::Oll, Subset::Coll];
+
+// Recent attempts of a case that count towards its weight
+const HISTORY: usize = 5;
+// Weight of a case that has never been drilled, so new cases come up early
+const UNSEEN_WEIGHT: f64 = 2.0;
+// Weight of a case that is not yet due again, relative to one that is
+const NOT_DUE_FACTOR: f64 = 0.1;
+
+pub fn config_key(subset: Subset) -> &'static str {
+    match subset {
+        Subset::Pll => "record_trainer_pll",
+        Subset::Oll => "record_trainer_oll",
+        Subset::Coll => "record_trainer_coll",
+        // Not offered as drills, but kept apart should they ever be
+        Subset::Zbll => "record_trainer_zbll",
+        Subset::LastSlot => "record_trainer_last_slot",
+        Subset::Cross => "record_trainer_cross",
+    }
+}
+
+#[derive(Debug)]
+pub struct Drill {
+    pub case: &'static Case,
+    pub state: CubeState,
+    pub setup: Vec<String>,
+    started: Instant,
+    pub recognised: Option<u32>,
+}
+impl Drill {
+    pub fn new(case: &'static Case) -> Drill {
+        let setup = training::last_layer_setup(&[case.alg]);
+
+        // Shown yellow side up, the way last layer cases are usually learnt
+        let mut state = CubeState::solved(3);
+        state.apply(&Move::Rotation {
+            face: Face::F,
+            amount: 2,
+        });
+        state.apply_all(&setup);
+
+        Drill {
+            case,
+            state,
+            setup: setup.iter().map(|mv| mv.to_string()).collect(),
+            started: Instant::now(),
+            recognised: None,
+        }
+    }
+    pub fn recognise(&mut self) {
+        if self.recognised.is_none() {
+            self.recognised = Some(timer::ms_from_duration(self.started.elapsed()));
+        }
+    }
+    /// The attempt as a solve, a DNF if the case was not known.
+    pub fn solve(&self, known: bool) -> Solve {
+        let mut solve = Solve::new(self.recognised.unwrap_or_default(), &self.setup);
+        solve.case = Some(self.case.name.to_string());
+        solve.dnf = !known;
+        solve
+    }
+}
+
+/// Pick the next case to drill, favouring cases that are slow to recognise or often missed.
+///
+/// Each case is due again after `2^n` drills, where `n` is how many times in a row it was
+/// recognised faster than the mean; cases that are not due are still picked, only rarely.
+/// The previous case is never repeated straight away.
+pub fn next_case(subset: Subset, record: &Record, previous: Option<&str>) -> Option<&'static Case> {
+    let cases = subset.cases();
+    let weights = weights(cases, record, previous);
+    let indices: Vec<usize> = (0..cases.len()).collect();
+    indices
+        .choose_weighted(&mut rand::rng(), |&i| weights[i])
+        .ok()
+        .map(|&i| &cases[i])
+        .or_else(|| cases.choose(&mut rand::rng()))
+}
+
+// How likely each case is to be picked next, relative to the others
+fn weights(cases: &[Case], record: &Record, previous: Option<&str>) -> Vec<f64> {
+    let known: Vec<u32> = record
+        .solves
+        .iter()
+        .filter(|s| !s.dnf)
+        .map(|s| s.value())
+        .collect();
+    let target = match known.len() {
+        0 => 1.0,
+        len => (known.iter().map(|&v| v as f64).sum::<f64>() / len as f64).max(1.0),
+    };
+
+    let weight = |case: &Case| {
+        if cases.len() > 1 && previous == Some(case.name) {
+            return 0.0;
+        }
+
+        // Most recent attempts first, with how many drills ago each one was
+        let history: Vec<(usize, &Solve)> = record
+            .solves
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.case.as_deref() == Some(case.name))
+            .take(HISTORY)
+            .collect();
+        let Some(&(last_seen, _)) = history.first() else {
+            return UNSEEN_WEIGHT;
+        };
+
+        // A miss counts as twice the mean
+        let slowness = history
+            .iter()
+            .map(|(_, s)| {
+                if s.dnf {
+                    2.0
+                } else {
+                    s.value() as f64 / target
+                }
+            })
+            .sum::<f64>()
+            / history.len() as f64;
+
+        let streak = history
+            .iter()
+            .take_while(|(_, s)| !s.dnf && s.value() as f64 <= target)
+            .count();
+        let due = last_seen + 1 >= 1 << streak;
+
+        slowness.powi(2) * if due { 1.0 } else { NOT_DUE_FACTOR }
+    };
+
+    cases.iter().map(weight).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Drills given oldest first, as (case, recognition time, known)
+    fn record(drills: &[(&str, u32, bool)]) -> Record {
+        let mut record = Record::default();
+        for &(case, time, known) in drills {
+            let mut solve = Solve::new(time, &vec![]);
+            solve.case = Some(case.to_string());
+            solve.dnf = !known;
+            record.add_solve(solve);
+        }
+        record
+    }
+
+    fn weight_of(cases: &[Case], weights: &[f64], name: &str) -> f64 {
+        weights[cases.iter().position(|case| case.name == name).unwrap()]
+    }
+
+    #[test]
+    fn unseen_cases_come_up_early() {
+        let weights = weights(training::PLL, &record(&[("Aa", 1_000, true)]), None);
+        assert_eq!(weight_of(training::PLL, &weights, "Ab"), UNSEEN_WEIGHT);
+        assert!(weight_of(training::PLL, &weights, "Aa") < UNSEEN_WEIGHT);
+    }
+
+    #[test]
+    fn slow_and_missed_cases_weigh_more() {
+        let record = record(&[
+            ("F", 2_000, true),
+            ("Aa", 1_000, true),
+            ("Ab", 3_000, true),
+            ("E", 2_000, false),
+        ]);
+        let weights = weights(training::PLL, &record, None);
+        let weight = |name| weight_of(training::PLL, &weights, name);
+        assert!(weight("Ab") > weight("F"));
+        assert!(weight("F") > weight("Aa"));
+        // A miss counts as twice the mean recognition time of 2s
+        assert_eq!(weight("E"), 4.0);
+        assert_eq!(weight("Ab"), 2.25);
+    }
+
+    #[test]
+    fn fast_streaks_put_a_case_off() {
+        // Two fast drills in a row make the case due again four drills later
+        let mut drills = vec![("Aa", 1_000, true), ("Aa", 1_000, true)];
+        drills.extend([("Ab", 3_000, true), ("E", 3_000, true)]);
+        let before = weights(training::PLL, &record(&drills), None);
+        let slowness: f64 = 1_000.0 / 2_000.0;
+        assert_eq!(
+            weight_of(training::PLL, &before, "Aa"),
+            slowness.powi(2) * NOT_DUE_FACTOR
+        );
+
+        drills.push(("F", 3_000, true));
+        let later = weights(training::PLL, &record(&drills), None);
+        assert!(weight_of(training::PLL, &later, "Aa") > slowness.powi(2) * NOT_DUE_FACTOR);
+    }
+
+    #[test]
+    fn never_repeats_the_previous_case() {
+        let record = record(&[("Aa", 1_000, true)]);
+        let weights = weights(training::PLL, &record, Some("Aa"));
+        assert_eq!(weight_of(training::PLL, &weights, "Aa"), 0.0);
+        for _ in 0..50 {
+            let case = next_case(Subset::Pll, &record, Some("Aa")).unwrap();
+            assert_ne!(case.name, "Aa");
+        }
+    }
+}
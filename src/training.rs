@@ -0,0 +1,752 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Training scrambles that set up a chosen case from one of the main 3x3x3 case groups.
+
+use crate::cube::{self, CubeState, Face, Move};
+use rand::RngExt;
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Case {
+    pub name: &'static str,
+    pub alg: &'static str,
+}
+
+const fn case(name: &'static str, alg: &'static str) -> Case {
+    Case { name, alg }
+}
+
+pub const PLL: &[Case] = &[
+    case("Aa", "x R' U R' D2 R U' R' D2 R2 x'"),
+    case("Ab", "x R2 D2 R U R' D2 R U' R x'"),
+    case("E", "x' R U' R' D R U R' D' R U R' D R U' R' D' x"),
+    case("F", "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R"),
+    case("Ga", "R2 U R' U R' U' R U' R2 U' D R' U R D'"),
+    case("Gb", "R' U' R U D' R2 U R' U R U' R U' R2 D"),
+    case("Gc", "R2 U' R U' R U R' U R2 U D' R U' R' D"),
+    case("Gd", "R U R' U' D R2 U' R U' R' U R' U R2 D'"),
+    case("H", "M2 U M2 U2 M2 U M2"),
+    case("Ja", "x R2 F R F' R U2 r' U r U2 x'"),
+    case("Jb", "R U R' F' R U R' U' R' F R2 U' R'"),
+    case(
+        "Na",
+        "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+    ),
+    case("Nb", "R' U R U' R' F' U' F R U R' F R' F' R U' R"),
+    case("Ra", "R U' R' U' R U R D R' U' R D' R' U2 R'"),
+    case("Rb", "R2 F R U R U' R' F' R U2 R' U2 R"),
+    case("T", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+    case("Ua", "M2 U M U2 M' U M2"),
+    case("Ub", "M2 U' M U2 M' U' M2"),
+    case("V", "R' U R' U' y R' F' R2 U' R' U R' F R F"),
+    case("Y", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    case("Z", "M' U M2 U M2 U M' U2 M2"),
+];
+
+pub const OLL: &[Case] = &[
+    case("OLL 1", "R U2 R2 F R F' U2 R' F R F'"),
+    case("OLL 2", "F R U R' U' F' f R U R' U' f'"),
+    case("OLL 3", "f R U R' U' f' U' F R U R' U' F'"),
+    case("OLL 4", "f R U R' U' f' U F R U R' U' F'"),
+    case("OLL 5", "r' U2 R U R' U r"),
+    case("OLL 6", "r U2 R' U' R U' r'"),
+    case("OLL 7", "r U R' U R U2 r'"),
+    case("OLL 8", "l' U' L U' L' U2 l"),
+    case("OLL 9", "R U R' U' R' F R2 U R' U' F'"),
+    case("OLL 10", "R U R' U R' F R F' R U2 R'"),
+    case("OLL 11", "r U R' U R' F R F' R U2 r'"),
+    case("OLL 12", "M' R' U' R U' R' U2 R U' R r'"),
+    case("OLL 13", "F U R U' R2 F' R U R U' R'"),
+    case("OLL 14", "R' F R U R' F' R F U' F'"),
+    case("OLL 15", "r' U' r R' U' R U r' U r"),
+    case("OLL 16", "r U r' R U R' U' r U' r'"),
+    case("OLL 17", "F R' F' R2 r' U R U' R' U' M'"),
+    case("OLL 18", "r U R' U R U2 r2 U' R U' R' U2 r"),
+    case("OLL 19", "r' R U R U R' U' M' R' F R F'"),
+    case("OLL 20", "r U R' U' M2 U R U' R' U' M'"),
+    case("OLL 21", "R U2 R' U' R U R' U' R U' R'"),
+    case("OLL 22", "R U2 R2 U' R2 U' R2 U2 R"),
+    case("OLL 23", "R2 D' R U2 R' D R U2 R"),
+    case("OLL 24", "r U R' U' r' F R F'"),
+    case("OLL 25", "F' r U R' U' r' F R"),
+    case("OLL 26", "R U2 R' U' R U' R'"),
+    case("OLL 27", "R U R' U R U2 R'"),
+    case("OLL 28", "r U R' U' r' R U R U' R'"),
+    case("OLL 29", "R U R' U' R U' R' F' U' F R U R'"),
+    case("OLL 30", "F R' F R2 U' R' U' R U R' F2"),
+    case("OLL 31", "R' U' F U R U' R' F' R"),
+    case("OLL 32", "L U F' U' L' U L F L'"),
+    case("OLL 33", "R U R' U' R' F R F'"),
+    case("OLL 34", "R U R2 U' R' F R U R U' F'"),
+    case("OLL 35", "R U2 R2 F R F' R U2 R'"),
+    case("OLL 36", "L' U' L U' L' U L U L F' L' F"),
+    case("OLL 37", "F R' F' R U R U' R'"),
+    case("OLL 38", "R U R' U R U' R' U' R' F R F'"),
+    case("OLL 39", "L F' L' U' L U F U' L'"),
+    case("OLL 40", "R' F R U R' U' F' U R"),
+    case("OLL 41", "R U R' U R U2 R' F R U R' U' F'"),
+    case("OLL 42", "R' U' R U' R' U2 R F R U R' U' F'"),
+    case("OLL 43", "F' U' L' U L F"),
+    case("OLL 44", "F U R U' R' F'"),
+    case("OLL 45", "F R U R' U' F'"),
+    case("OLL 46", "R' U' R' F R F' U R"),
+    case("OLL 47", "R' U' R' F R F' R' F R F' U R"),
+    case("OLL 48", "F R U R' U' R U R' U' F'"),
+    case("OLL 49", "r U' r2 U r2 U r2 U' r"),
+    case("OLL 50", "r' U r2 U' r2 U' r2 U r'"),
+    case("OLL 51", "F U R U' R' U R U' R' F'"),
+    case("OLL 52", "R U R' U R U' B U' B' R'"),
+    case("OLL 53", "l' U2 L U L' U' L U L' U l"),
+    case("OLL 54", "r U2 R' U' R U R' U' R U' r'"),
+    case("OLL 55", "R' F R U R U' R2 F' R2 U' R' U R U R'"),
+    case("OLL 56", "r' U' r U' R' U R U' R' U R r' U r"),
+    case("OLL 57", "R U R' U' M' U R U' r'"),
+];
+
+// Each COLL case is named by its set and the corners that need swapping, held as for the
+// set's OCLL algorithm
+pub const COLL: &[Case] = &[
+    case("COLL T no swap", "R U R' U R U2 R' L' U' L U' L' U2 L"),
+    case("COLL T front swap", "r U R' U' r' F R F'"),
+    case("COLL T right swap", "F R U R' U' R U' R' U' R U R' F'"),
+    case("COLL T back swap", "l' U' L U R U' r' F"),
+    case("COLL T left swap", "R' F R U R' U' R' F' R2 U' R' U2 R"),
+    case(
+        "COLL T diagonal swap",
+        "F R' F' r U R U' r' U L' U R U' L U R'",
+    ),
+    case("COLL U no swap", "R U R' U R U2 R2 U' R U' R' U2 R"),
+    case("COLL U front swap", "F R U' R' U R U R' U R U' R' F'"),
+    case("COLL U right swap", "R2 D R' U2 R D' R' U2 R'"),
+    case(
+        "COLL U back swap",
+        "L' U2 L U L' U L R U2 R D R' U2 R D' R2",
+    ),
+    case("COLL U left swap", "R2 D' R U2 R' D R U2 R"),
+    case("COLL U diagonal swap", "R' U2 R F U' R' U' R U F'"),
+    case("COLL L no swap", "R U R' U R U2 R' U R' U' R U' R' U2 R"),
+    case("COLL L front swap", "F R' F' r U R U' r'"),
+    case("COLL L right swap", "R U2 R D R' U2 R D' R2"),
+    case("COLL L back swap", "R' U2 R' D' R U2 R' D R2"),
+    case("COLL L left swap", "F' r U R' U' r' F R"),
+    case(
+        "COLL L diagonal swap",
+        "F R U R' U' R U' R' U2 R U2 R' U' F'",
+    ),
+    case("COLL H no swap", "R U2 R' U' R U R' U' R U' R'"),
+    case("COLL H front swap", "R U R' U R U L' U R' U' L"),
+    case("COLL H right swap", "F R U' R' U R U2 R' U' R U R' U' F'"),
+    case("COLL H diagonal swap", "F R U R' U' R U R' U' R U R' U' F'"),
+    case("COLL Pi no swap", "R U2 R2 U' R2 U' R2 U2 R"),
+    case(
+        "COLL Pi front swap",
+        "R U R' U' R' F R2 U R' U' R U R' U' F'",
+    ),
+    case("COLL Pi right swap", "F U R U' R' U R U2 R' U' R U R' F'"),
+    case("COLL Pi back swap", "R' F2 R U2 R U2 R' F2 U' R U' R'"),
+    case("COLL Pi left swap", "R U' L' U R' U L U L' U L"),
+    case(
+        "COLL Pi diagonal swap",
+        "R U2 R' U' F' R U2 R' U' R U' R' F R U' R'",
+    ),
+    case("COLL S no swap", "R U R' U R U2 R'"),
+    case("COLL S front swap", "R2 D' R U' R' D R U' R U R' U R"),
+    case("COLL S right swap", "R2 F R U R U' R' F' R U' R' U R"),
+    case("COLL S back swap", "R U R' U R2 D R' U2 R D' R2"),
+    case("COLL S left swap", "R U' L' U R' U' L"),
+    case(
+        "COLL S diagonal swap",
+        "r U R' U' r' F R F' R U' L' U R' U' L",
+    ),
+    case("COLL AS no swap", "R U2 R' U' R U' R'"),
+    case("COLL AS front swap", "R2 D R' U2 R D' R2 U' R U' R'"),
+    case("COLL AS right swap", "R' U' R U' R' U R' D' R U R' D R2"),
+    case("COLL AS back swap", "L' U R U' L U R'"),
+    case("COLL AS left swap", "R' U' R U' R2 D' R U2 R' D R2"),
+    case(
+        "COLL AS diagonal swap",
+        "R' F' r U R U' r' F L' U R U' L U R'",
+    ),
+    case(
+        "COLL O diagonal swap",
+        "F R U' R' U' R U R' F' R U R' U' R' F R F'",
+    ),
+    case("COLL O adjacent swap", "R' F R' B2 R F' R' B2 R2"),
+];
+
+// Each entry is a whole ZBLL set, named by its corner orientation, rather than a single case:
+// the set is set up from its OCLL case followed by a random permutation of the last layer
+pub const ZBLL: &[Case] = &[
+    case("ZBLL T", "r U R' U' r' F R F'"),
+    case("ZBLL U", "R2 D' R U2 R' D R U2 R"),
+    case("ZBLL L", "F' r U R' U' r' F R"),
+    case("ZBLL H", "R U2 R' U' R U R' U' R U' R'"),
+    case("ZBLL Pi", "R U2 R2 U' R2 U' R2 U2 R"),
+    case("ZBLL S", "R U R' U R U2 R'"),
+    case("ZBLL AS", "R U2 R' U' R U' R'"),
+];
+
+pub const LAST_SLOT: &[Case] = &[
+    case("Pair in top layer", ""),
+    case("Corner in slot", ""),
+    case("Edge in slot", ""),
+    case("Pair in slot", ""),
+];
+
+// Inserts that only disturb the front right slot and the last layer
+const INSERTS: &[&str] = &[
+    "R U R'", "R U' R'", "R U2 R'", "F' U F", "F' U' F", "F' U2 F",
+];
+
+pub const CROSS: &[Case] = &[
+    case("Cross in 4", ""),
+    case("Cross in 5", ""),
+    case("Cross in 6", ""),
+    case("Cross in 7", ""),
+    case("Cross in 8", ""),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Subset {
+    #[default]
+    Pll,
+    Oll,
+    Coll,
+    Zbll,
+    LastSlot,
+    Cross,
+}
+impl Subset {
+    pub const ALL: [Subset; 6] = [
+        Subset::Pll,
+        Subset::Oll,
+        Subset::Coll,
+        Subset::Zbll,
+        Subset::LastSlot,
+        Subset::Cross,
+    ];
+
+    pub fn as_string(&self) -> String {
+        match self {
+            Subset::Pll => "PLL".to_string(),
+            Subset::Oll => "OLL".to_string(),
+            Subset::Coll => "COLL".to_string(),
+            Subset::Zbll => "ZBLL sets".to_string(),
+            Subset::LastSlot => "Last slot".to_string(),
+            Subset::Cross => "Cross".to_string(),
+        }
+    }
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            Subset::Pll => "record_training_pll",
+            Subset::Oll => "record_training_oll",
+            Subset::Coll => "record_training_coll",
+            Subset::Zbll => "record_training_zbll",
+            Subset::LastSlot => "record_training_last_slot",
+            Subset::Cross => "record_training_cross",
+        }
+    }
+    pub fn cases(&self) -> &'static [Case] {
+        match self {
+            Subset::Pll => PLL,
+            Subset::Oll => OLL,
+            Subset::Coll => COLL,
+            Subset::Zbll => ZBLL,
+            Subset::LastSlot => LAST_SLOT,
+            Subset::Cross => CROSS,
+        }
+    }
+}
+
+/// Generate a scramble for a random case out of `selected`, returning the case name with it.
+pub fn generate(subset: Subset, selected: &[String]) -> Option<(String, Vec<String>)> {
+    let cases: Vec<&Case> = subset
+        .cases()
+        .iter()
+        .filter(|case| selected.iter().any(|name| name == case.name))
+        .collect();
+    let case = cases.choose(&mut rand::rng())?;
+
+    let setup = match subset {
+        Subset::Pll | Subset::Oll | Subset::Coll => last_layer_setup(&[case.alg]),
+        Subset::Zbll => {
+            // The OCLL case on its own, with the last layer already permuted, is part of the set
+            let plls: Vec<&str> = PLL.iter().map(|pll| pll.alg).chain([""]).collect();
+            last_layer_setup(&[case.alg, plls.choose(&mut rand::rng())?])
+        }
+        Subset::LastSlot => last_slot_setup(case.name)?,
+        Subset::Cross => cross_setup(case.name)?,
+    };
+
+    Some((
+        case.name.to_string(),
+        setup.iter().map(|mv| mv.to_string()).collect(),
+    ))
+}
+
+fn parse(alg: &str) -> Vec<Move> {
+    alg.split_whitespace().filter_map(Move::parse).collect()
+}
+
+fn random_auf() -> Vec<Move> {
+    match rand::rng().random_range(0..4u8) {
+        0 => vec![],
+        amount => vec![Move::Turn {
+            face: Face::U,
+            depth: 1,
+            amount,
+        }],
+    }
+}
+
+/// Undo each algorithm in turn, from last to first, with a random AUF around each one.
+pub fn last_layer_setup(algs: &[&str]) -> Vec<Move> {
+    let mut setup = random_auf();
+    for alg in algs.iter().rev() {
+        setup.extend(cube::invert(&parse(alg)));
+        setup.extend(random_auf());
+    }
+    cube::face_turns(&setup)
+}
+
+fn last_slot_setup(name: &str) -> Option<Vec<Move>> {
+    let corner = [Face::D, Face::F, Face::R];
+    let edge = [Face::F, Face::R];
+
+    for _ in 0..1000 {
+        let mut setup = vec![];
+        for _ in 0..rand::rng().random_range(2..5) {
+            setup.extend(random_auf());
+            setup.extend(parse(INSERTS.choose(&mut rand::rng())?));
+        }
+        setup.extend(random_auf());
+        let setup = cube::face_turns(&setup);
+
+        let mut state = CubeState::solved(3);
+        state.apply_all(&setup);
+        if state.layers_solved(2) {
+            continue;
+        }
+        let corner_home = state.locate(&corner) == Some(state.home(&corner));
+        let edge_home = state.locate(&edge) == Some(state.home(&edge));
+        let found = match (corner_home, edge_home) {
+            (false, false) => LAST_SLOT[0].name,
+            (true, false) => LAST_SLOT[1].name,
+            (false, true) => LAST_SLOT[2].name,
+            (true, true) => LAST_SLOT[3].name,
+        };
+        if found == name {
+            return Some(setup);
+        }
+    }
+    None
+}
+
+fn cross_setup(name: &str) -> Option<Vec<Move>> {
+    let faces = [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+    // An eight move cross turns up in roughly one scramble out of two thousand
+    for _ in 0..20_000 {
+        // Random moves, never turning the same axis twice in a row
+        let mut setup: Vec<Move> = vec![];
+        while setup.len() < 25 {
+            let face = *faces.choose(&mut rand::rng())?;
+            if let Some(Move::Turn { face: last, .. }) = setup.last()
+                && (*last == face || last.opposite() == face)
+            {
+                continue;
+            }
+            setup.push(Move::Turn {
+                face,
+                depth: 1,
+                amount: rand::rng().random_range(1..4),
+            });
+        }
+
+        if format!("Cross in {}", cross_length(&setup)) == name {
+            return Some(setup);
+        }
+    }
+    None
+}
+
+const CROSS_EDGES: [[Face; 2]; 4] = [
+    [Face::D, Face::F],
+    [Face::D, Face::R],
+    [Face::D, Face::B],
+    [Face::D, Face::L],
+];
+
+// Every edge sticker slot (position and facing) on a 3x3x3, two per edge
+static EDGE_SLOTS: LazyLock<Vec<([i32; 3], [i32; 3])>> = LazyLock::new(|| {
+    let mut slots = vec![];
+    let state = CubeState::solved(3);
+    for a in Face::ALL {
+        for b in Face::ALL {
+            if a != b && a != b.opposite() {
+                let pos = state.home(&[a, b]);
+                let normal = state.home(&[a]).map(|c| c / 2);
+                slots.push((pos, normal));
+            }
+        }
+    }
+    slots
+});
+
+// Edge slot tables for every outer face turn, indexed by `face_turn_index`
+static FACE_TURN_TABLES: LazyLock<Vec<Vec<usize>>> = LazyLock::new(|| {
+    Face::ALL
+        .iter()
+        .flat_map(|&face| {
+            (1..4).map(move |amount| Move::Turn {
+                face,
+                depth: 1,
+                amount,
+            })
+        })
+        .map(|mv| edge_slot_table(&mv))
+        .collect()
+});
+
+fn face_turn_index(mv: &Move) -> Option<usize> {
+    match *mv {
+        Move::Turn {
+            face,
+            depth: 1,
+            amount: amount @ 1..=3,
+        } => Some(Face::ALL.iter().position(|f| *f == face)? * 3 + amount as usize - 1),
+        _ => None,
+    }
+}
+
+// Optimal move count to solve the D cross from every arrangement of its four edges
+static CROSS_DISTANCES: LazyLock<Vec<u8>> = LazyLock::new(|| {
+    let tables = &*FACE_TURN_TABLES;
+    let mut distances = vec![u8::MAX; EDGE_SLOTS.len().pow(4)];
+    let solved = cross_index(&solved_cross());
+    distances[solved] = 0;
+    let mut frontier = vec![solved_cross()];
+    let mut depth = 0;
+    while !frontier.is_empty() {
+        depth += 1;
+        let mut next = vec![];
+        for state in &frontier {
+            for table in tables {
+                let moved = state.map(|slot| table[slot]);
+                let index = cross_index(&moved);
+                if distances[index] == u8::MAX {
+                    distances[index] = depth;
+                    next.push(moved);
+                }
+            }
+        }
+        frontier = next;
+    }
+    distances
+});
+
+// Where each edge sticker slot is sent by a move
+fn edge_slot_table(mv: &Move) -> Vec<usize> {
+    EDGE_SLOTS
+        .iter()
+        .map(|&(pos, normal)| {
+            let moved = mv.transform(3, pos, normal);
+            EDGE_SLOTS
+                .iter()
+                .position(|&slot| slot == moved)
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+// Slot of the D sticker of each cross edge
+fn solved_cross() -> [usize; 4] {
+    let state = CubeState::solved(3);
+    CROSS_EDGES.map(|edge| {
+        let slot = (state.home(&edge), state.home(&[Face::D]).map(|c| c / 2));
+        EDGE_SLOTS.iter().position(|&s| s == slot).unwrap_or(0)
+    })
+}
+
+fn cross_index(state: &[usize; 4]) -> usize {
+    state
+        .iter()
+        .fold(0, |index, &slot| index * EDGE_SLOTS.len() + slot)
+}
+
+/// Number of moves in an optimal solution to the D cross after `moves`.
+pub fn cross_length(moves: &[Move]) -> u8 {
+    let mut state = solved_cross();
+    for mv in moves {
+        state = match face_turn_index(mv) {
+            Some(index) => state.map(|slot| FACE_TURN_TABLES[index][slot]),
+            None => {
+                let table = edge_slot_table(mv);
+                state.map(|slot| table[slot])
+            }
+        };
+    }
+    CROSS_DISTANCES[cross_index(&state)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_after(setup: &[String]) -> CubeState {
+        CubeState::scrambled(3, setup).unwrap()
+    }
+
+    fn auf(amount: u8) -> Vec<Move> {
+        match amount {
+            0 => vec![],
+            amount => vec![Move::Turn {
+                face: Face::U,
+                depth: 1,
+                amount,
+            }],
+        }
+    }
+
+    // Whether the algorithm, with some AUF before and after, takes the state to one that passes
+    // `check`
+    fn alg_reaches(state: &CubeState, alg: &str, check: impl Fn(&CubeState) -> bool) -> bool {
+        (0..4).any(|before| {
+            (0..4).any(|after| {
+                let mut state = state.clone();
+                state.apply_all(&auf(before));
+                state.apply_all(&parse(alg));
+                state.apply_all(&auf(after));
+                check(&state)
+            })
+        })
+    }
+
+    #[test]
+    fn needs_a_selected_case() {
+        assert_eq!(generate(Subset::Pll, &[]), None);
+        // Cases from another group don't count
+        assert_eq!(generate(Subset::Pll, &["OLL 1".to_string()]), None);
+    }
+
+    #[test]
+    fn picks_only_selected_cases() {
+        let chosen = vec!["Aa".to_string(), "T".to_string()];
+        for _ in 0..20 {
+            let (case, _) = generate(Subset::Pll, &chosen).unwrap();
+            assert!(chosen.contains(&case), "{case}");
+        }
+    }
+
+    #[test]
+    fn last_layer_cases_are_solved_by_their_algorithm() {
+        for subset in [Subset::Pll, Subset::Oll, Subset::Coll] {
+            for case in subset.cases() {
+                let (name, setup) = generate(subset, &[case.name.to_string()]).unwrap();
+                assert_eq!(name, case.name);
+                let state = state_after(&setup);
+                assert!(state.layers_solved(2), "{name}");
+                assert!(
+                    alg_reaches(&state, case.alg, CubeState::is_solved),
+                    "{name}"
+                );
+            }
+        }
+    }
+
+    // The four last layer corners in turn around the U face, as home slots
+    const LAST_LAYER_CORNERS: [[Face; 3]; 4] = [
+        [Face::U, Face::R, Face::F],
+        [Face::U, Face::F, Face::L],
+        [Face::U, Face::L, Face::B],
+        [Face::U, Face::B, Face::R],
+    ];
+
+    // Which last layer corner is in each slot, and which way its U sticker faces
+    fn last_layer_corners(state: &CubeState) -> [(usize, usize); 4] {
+        LAST_LAYER_CORNERS.map(|slot| {
+            let pos = state.home(&slot);
+            let colors = slot.map(|face| state.color_at(pos, face).unwrap());
+            let piece = LAST_LAYER_CORNERS
+                .iter()
+                .position(|corner| corner.iter().all(|color| colors.contains(color)))
+                .unwrap();
+            let twist = colors.iter().position(|&color| color == Face::U).unwrap();
+            (piece, twist)
+        })
+    }
+
+    // The corners that still need swapping after the best AUF, by the slots they are in
+    fn corner_swap(corners: &[(usize, usize); 4]) -> &'static str {
+        for auf in 0..4 {
+            let wrong: Vec<usize> = (0..4)
+                .filter(|&slot| corners[slot].0 != (slot + auf) % 4)
+                .collect();
+            match wrong[..] {
+                [] => return "no swap",
+                [0, 1] => return "front swap",
+                [1, 2] => return "left swap",
+                [2, 3] => return "back swap",
+                [0, 3] => return "right swap",
+                [0, 2] | [1, 3] => return "diagonal swap",
+                _ => {}
+            }
+        }
+        unreachable!("corners can always be solved by at most one swap and an AUF")
+    }
+
+    // Every name the COLL case set up by `setup` goes by, held as for the OCLL algorithm of its
+    // set, which for symmetric sets means more than one way round
+    fn coll_names(setup: &[Move]) -> Vec<String> {
+        let ocll = [
+            ("H", "OLL 21"),
+            ("Pi", "OLL 22"),
+            ("U", "OLL 23"),
+            ("T", "OLL 24"),
+            ("L", "OLL 25"),
+            ("AS", "OLL 26"),
+            ("S", "OLL 27"),
+        ];
+        let mut names = vec![];
+        for amount in 0..4 {
+            let mut state = CubeState::solved(3);
+            state.apply_all(setup);
+            state.apply_all(&auf(amount));
+            let corners = last_layer_corners(&state);
+            let swap = corner_swap(&corners);
+
+            if corners.iter().all(|&(_, twist)| twist == 0) {
+                let swap = match swap {
+                    "no swap" | "diagonal swap" => swap,
+                    _ => "adjacent swap",
+                };
+                names.push(format!("COLL O {swap}"));
+            }
+            for (set, oll) in ocll {
+                let alg = OLL.iter().find(|case| case.name == oll).unwrap().alg;
+                let mut reference = CubeState::solved(3);
+                reference.apply_all(&cube::invert(&parse(alg)));
+                let twists = |corners: [(usize, usize); 4]| corners.map(|(_, twist)| twist);
+                if twists(corners) == twists(last_layer_corners(&reference)) {
+                    names.push(format!("COLL {set} {swap}"));
+                }
+            }
+        }
+        names
+    }
+
+    #[test]
+    fn coll_algorithms_solve_the_case_they_are_named_after() {
+        let edges = [Face::R, Face::F, Face::L, Face::B].map(|face| [Face::U, face]);
+        for case in COLL {
+            let alg = parse(case.alg);
+            let mut state = CubeState::solved(3);
+            state.apply_all(&alg);
+            // Only the last layer corners are solved, with the edges kept oriented
+            assert!(state.layers_solved(2), "{}", case.name);
+            for edge in edges {
+                assert_eq!(
+                    state.color_at(state.home(&edge), Face::U),
+                    Some(Face::U),
+                    "{}",
+                    case.name
+                );
+            }
+            let names = coll_names(&cube::invert(&alg));
+            assert!(
+                names.iter().any(|name| name == case.name),
+                "{}: {names:?}",
+                case.name
+            );
+        }
+    }
+
+    #[test]
+    fn coll_has_every_case_once() {
+        // H cases look the same from either side, and the O cases exclude the solved one
+        assert_eq!(COLL.len(), 4 + 6 * 6 + 2);
+        for (i, case) in COLL.iter().enumerate() {
+            assert!(
+                COLL[..i].iter().all(|other| other.name != case.name),
+                "{}",
+                case.name
+            );
+        }
+    }
+
+    #[test]
+    fn zbll_sets_are_oriented_by_their_ocll_algorithm() {
+        let oriented = |state: &CubeState| {
+            state
+                .face_colors(Face::U)
+                .iter()
+                .flatten()
+                .all(|&color| color == Face::U)
+        };
+        for case in ZBLL {
+            for _ in 0..5 {
+                let (_, setup) = generate(Subset::Zbll, &[case.name.to_string()]).unwrap();
+                let state = state_after(&setup);
+                assert!(state.layers_solved(2), "{}", case.name);
+                assert!(alg_reaches(&state, case.alg, oriented), "{}", case.name);
+            }
+        }
+    }
+
+    #[test]
+    fn last_slot_cases_only_leave_the_front_right_slot() {
+        let corner = [Face::D, Face::F, Face::R];
+        let edge = [Face::F, Face::R];
+        for case in LAST_SLOT {
+            let (_, setup) = generate(Subset::LastSlot, &[case.name.to_string()]).unwrap();
+            let state = state_after(&setup);
+            assert!(!state.layers_solved(2), "{}", case.name);
+            assert_eq!(cross_length(&parse(&setup.join(" "))), 0, "{}", case.name);
+            for piece in [
+                &[Face::D, Face::F, Face::L][..],
+                &[Face::D, Face::B, Face::L],
+                &[Face::D, Face::B, Face::R],
+                &[Face::F, Face::L],
+                &[Face::B, Face::L],
+                &[Face::B, Face::R],
+            ] {
+                assert_eq!(
+                    state.locate(piece),
+                    Some(state.home(piece)),
+                    "{}",
+                    case.name
+                );
+            }
+            let corner_home = state.locate(&corner) == Some(state.home(&corner));
+            let edge_home = state.locate(&edge) == Some(state.home(&edge));
+            let expected = match case.name {
+                "Pair in top layer" => (false, false),
+                "Corner in slot" => (true, false),
+                "Edge in slot" => (false, true),
+                _ => (true, true),
+            };
+            assert_eq!((corner_home, edge_home), expected, "{}", case.name);
+        }
+    }
+
+    #[test]
+    fn cross_cases_have_their_optimal_length() {
+        for length in 4..=6 {
+            let name = format!("Cross in {length}");
+            let (_, setup) = generate(Subset::Cross, std::slice::from_ref(&name)).unwrap();
+            assert_eq!(cross_length(&parse(&setup.join(" "))), length, "{name}");
+        }
+    }
+
+    #[test]
+    fn measures_cross_length() {
+        assert_eq!(cross_length(&[]), 0);
+        assert_eq!(cross_length(&parse("U D2 U'")), 1);
+        assert_eq!(cross_length(&parse("R")), 1);
+        assert_eq!(cross_length(&parse("F R")), 2);
+        // Last layer algorithms leave the cross alone
+        assert_eq!(cross_length(&parse(PLL[0].alg)), 0);
+    }
+}
@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! What starts and stops the timer: a key, such as the space bar or a USB foot pedal that
+//! types one, a mouse button, or a button on a gamepad.
+//!
+//! Every input is held to get ready and released to start, the same as the space bar.
+
+use cosmic::iced::futures::{SinkExt, Stream, channel::mpsc};
+use cosmic::iced::{keyboard, mouse, stream};
+use gilrs::{EventType, Gilrs};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc::unbounded_channel;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Input {
+    /// A character, in lower case, or the name of a key such as "Enter".
+    Key(String),
+    /// A mouse button, such as "Left" or "Back".
+    Mouse(String),
+    /// A gamepad button, such as "South", or "Code(n)" for one without a standard mapping.
+    Gamepad(String),
+}
+impl Default for Input {
+    fn default() -> Self {
+        Input::Key(" ".to_string())
+    }
+}
+impl Input {
+    pub fn is_gamepad(&self) -> bool {
+        matches!(self, Input::Gamepad(_))
+    }
+}
+
+pub fn key(key: &keyboard::Key) -> Option<Input> {
+    match key.as_ref() {
+        keyboard::Key::Character(c) => Some(Input::Key(c.to_lowercase())),
+        keyboard::Key::Named(named) => Some(Input::Key(format!("{named:?}"))),
+        keyboard::Key::Unidentified => None,
+    }
+}
+
+pub fn mouse(button: mouse::Button) -> Input {
+    Input::Mouse(format!("{button:?}"))
+}
+
+/// Button presses (true) and releases (false) from every connected gamepad.
+pub fn gamepad_events() -> impl Stream<Item = (Input, bool)> {
+    stream::channel(100, |mut output: mpsc::Sender<(Input, bool)>| async move {
+        let (sender, mut events) = unbounded_channel();
+        // Gamepads are polled on a thread of their own, as gilrs blocks while waiting
+        std::thread::spawn(move || {
+            let mut gilrs = match Gilrs::new() {
+                Ok(gilrs) => gilrs,
+                Err(err) => {
+                    tracing::error!("Failed to read gamepads: {err}");
+                    return;
+                }
+            };
+            while !sender.is_closed() {
+                let Some(event) = gilrs.next_event_blocking(Some(Duration::from_secs(1))) else {
+                    continue;
+                };
+                let (button, code, pressed) = match event.event {
+                    EventType::ButtonPressed(button, code) => (button, code, true),
+                    EventType::ButtonReleased(button, code) => (button, code, false),
+                    _ => continue,
+                };
+                let name = match button {
+                    gilrs::Button::Unknown => format!("Code({})", code.into_u32()),
+                    button => format!("{button:?}"),
+                };
+                let _ = sender.send((Input::Gamepad(name), pressed));
+            }
+        });
+
+        while let Some(event) = events.recv().await {
+            if output.send(event).await.is_err() {
+                return;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_ignore_case() {
+        assert_eq!(
+            key(&keyboard::Key::Character("J".into())),
+            Some(Input::Key("j".to_string()))
+        );
+        assert_eq!(
+            key(&keyboard::Key::Named(keyboard::key::Named::Enter)),
+            Some(Input::Key("Enter".to_string()))
+        );
+        assert_eq!(key(&keyboard::Key::Unidentified), None);
+    }
+}
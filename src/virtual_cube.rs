@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! An on-screen cube turned from the keyboard, using the same key mapping as csTimer.
+
+use crate::cube::{CubeState, Move};
+
+// Keys for each move, with the right hand on the R, U and F turns as in csTimer and qqTimer
+const KEYS: [(char, &str); 32] = [
+    ('i', "R"),
+    ('k', "R'"),
+    ('w', "B"),
+    ('o', "B'"),
+    ('s', "D"),
+    ('l', "D'"),
+    ('d', "L"),
+    ('e', "L'"),
+    ('j', "U"),
+    ('f', "U'"),
+    ('h', "F"),
+    ('g', "F'"),
+    (';', "y"),
+    ('a', "y'"),
+    ('u', "r"),
+    ('m', "r'"),
+    ('r', "l'"),
+    ('v', "l"),
+    ('t', "x"),
+    ('y', "x"),
+    ('n', "x'"),
+    ('b', "x'"),
+    ('.', "M'"),
+    ('x', "M'"),
+    ('5', "M"),
+    ('6', "M"),
+    ('p', "z"),
+    ('q', "z'"),
+    ('z', "d"),
+    ('/', "d'"),
+    (',', "u"),
+    ('c', "u'"),
+];
+
+/// Move made by a key press, if the key is mapped to one.
+pub fn key_move(key: &str) -> Option<Move> {
+    let mut chars = key.chars();
+    let key = chars.next()?.to_ascii_lowercase();
+    if chars.next().is_some() {
+        return None;
+    }
+    KEYS.iter()
+        .find(|(k, _)| *k == key)
+        .and_then(|(_, token)| Move::parse(token))
+}
+
+#[derive(Debug, Clone)]
+pub struct VirtualCube {
+    pub state: CubeState,
+    /// Every move made since the scramble, rotations included.
+    pub moves: Vec<Move>,
+}
+impl VirtualCube {
+    /// A cube of `size` with the scramble applied, if every move in it is understood.
+    pub fn new(size: usize, scramble: &[String]) -> Option<VirtualCube> {
+        Some(VirtualCube {
+            state: CubeState::scrambled(size, scramble)?,
+            moves: vec![],
+        })
+    }
+
+    pub fn turn(&mut self, mv: Move) {
+        self.state.apply(&mv);
+        self.moves.push(mv);
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.state.is_solved()
+    }
+
+    /// The moves made, in notation, to be saved with the solve.
+    pub fn solution(&self) -> String {
+        self.moves
+            .iter()
+            .map(|mv| mv.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube;
+
+    fn scramble(text: &str) -> Vec<String> {
+        text.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn type_keys(cube: &mut VirtualCube, keys: &str) {
+        for key in keys.chars() {
+            cube.turn(key_move(&key.to_string()).unwrap());
+        }
+    }
+
+    #[test]
+    fn keys_map_to_moves_as_in_cstimer() {
+        assert_eq!(key_move("i"), Move::parse("R"));
+        assert_eq!(key_move("k"), Move::parse("R'"));
+        assert_eq!(key_move(";"), Move::parse("y"));
+        assert_eq!(key_move("u"), Move::parse("Rw"));
+        assert_eq!(key_move("."), Move::parse("M'"));
+        // With shift or caps lock on
+        assert_eq!(key_move("J"), Move::parse("U"));
+        assert_eq!(key_move("1"), None);
+        assert_eq!(key_move(""), None);
+        // Only single characters are read, so named keys such as "Enter" aren't taken for "e"
+        assert_eq!(key_move("ik"), None);
+        assert_eq!(key_move("Enter"), None);
+    }
+
+    #[test]
+    fn rejects_scrambles_it_cannot_apply() {
+        assert!(VirtualCube::new(3, &scramble("R U Q")).is_none());
+        assert!(VirtualCube::new(2, &scramble("R M U")).is_none());
+        assert!(VirtualCube::new(3, &scramble("R M U")).is_some());
+    }
+
+    #[test]
+    fn typing_the_inverse_solves_the_scramble() {
+        let mut cube = VirtualCube::new(3, &scramble("R U F' L B' D")).unwrap();
+        assert!(!cube.is_solved());
+        // D' B L' F U' R'
+        type_keys(&mut cube, "lwehfk");
+        assert!(cube.is_solved());
+        assert_eq!(cube.moves.len(), 6);
+    }
+
+    #[test]
+    fn solution_reads_back_as_the_moves_made() {
+        let mut cube = VirtualCube::new(3, &scramble("R U")).unwrap();
+        type_keys(&mut cube, ";ifu.pk");
+        assert_eq!(cube.solution(), "y R U' Rw M' z R'");
+        assert_eq!(
+            cube::parse_moves(&cube.solution(), 3),
+            Ok(cube.moves.clone())
+        );
+    }
+}